@@ -1,6 +1,18 @@
+use crate::export::ExportKind;
+use crate::init::Shell;
+use crate::operation::{
+    Annotation, GcOptions, Items, ListOptions, ListStyle, NamespaceOperation, Operation, Update,
+};
+use crate::output::template::Template;
+use crate::stats::Kind;
+use crate::timestamp::parse_timestamp;
 use anyhow::{anyhow, Result};
-use clap::{builder::OsStr, Args, Parser, ValueEnum};
+use clap::{
+    builder::NonEmptyStringValueParser, Args, CommandFactory, FromArgMatches, Parser, Subcommand,
+    ValueEnum,
+};
 use directories::ProjectDirs;
+use std::ffi::OsString;
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -8,33 +20,757 @@ use std::path::PathBuf;
 #[command(author = env!("CARGO_PKG_AUTHORS"))]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Use a non-default store file
-    #[arg(long = "store_name", conflicts_with = "store")]
+    #[arg(long = "store_name", conflicts_with = "store", global = true)]
     pub store_name: Option<PathBuf>,
 
     /// Use a non-default filename for the store file in the default store directory
-    #[arg(long, conflicts_with = "store_name")]
+    #[arg(long, conflicts_with = "store_name", global = true)]
     pub store: Option<PathBuf>,
 
     #[command(flatten)]
     pub updates: UpdateArgs,
 
+    /// Add the visit or weight as of TIME, in seconds since the epoch or as an RFC 3339 timestamp
+    #[arg(
+        long,
+        value_name = "TIME",
+        value_parser = parse_timestamp,
+        requires = "UpdateArgs",
+        conflicts_with = "delete"
+    )]
+    pub at: Option<f64>,
+
+    /// Set the kind of the items added with --add or --increase instead of guessing it,
+    /// or only print items of KIND with --sorted, --stat or --tree
+    #[arg(
+        long,
+        value_enum,
+        value_name = "KIND",
+        conflicts_with_all = ["delete", "pick", "best", "batch", "import", "export"]
+    )]
+    pub kind: Option<Kind>,
+
     #[command(flatten)]
     pub stats: StatsArgs,
 
-    /// The method to sort output by
-    #[arg(long="sort_method", value_enum, default_value = SortMethod::Frecent)]
-    pub sort_method: SortMethod,
+    /// The method to sort output by (default frecent)
+    #[arg(long = "sort_method", value_enum, global = true)]
+    pub sort_method: Option<SortMethod>,
+
+    /// Use the namespace NAME in the store file (default "default")
+    #[arg(long = "ns", value_name = "NAME", global = true)]
+    pub namespace: Option<String>,
+
+    /// Use the settings of profile NAME from the config file
+    #[arg(long, value_name = "NAME", global = true)]
+    pub profile: Option<String>,
 
     #[command(flatten)]
     pub janitor: JanitorArgs,
 
-    /// The item to update
-    pub item: Option<String>,
+    /// Separate items with NUL instead of newline in output and in input read with --stdin
+    #[arg(short = '0', long, global = true)]
+    pub null: bool,
+
+    /// Read the items to update from stdin, one per line
+    #[arg(
+        long,
+        group = "targets",
+        requires = "UpdateArgs",
+        conflicts_with = "item"
+    )]
+    pub stdin: bool,
+
+    /// Apply add, adjust and delete operations read from stdin, one per line
+    #[arg(long, conflicts_with_all = ["UpdateArgs", "StatsArgs", "stdin", "item"])]
+    pub batch: bool,
+
+    /// Print every item in the format of another tool's database
+    #[arg(
+        long,
+        value_enum,
+        value_name = "KIND",
+        conflicts_with_all = ["UpdateArgs", "StatsArgs", "batch", "stdin", "item", "import"]
+    )]
+    pub export: Option<ExportKind>,
+
+    /// Print the stored statistics of ITEM, how its scores are calculated from them,
+    /// and when its frecency will decay below 1 and 0.1
+    #[arg(
+        long,
+        value_name = "ITEM",
+        conflicts_with_all = ["UpdateArgs", "StatsArgs", "batch", "stdin", "item", "import", "export", "kind"]
+    )]
+    pub explain: Option<String>,
+
+    /// Import items from another tool's database of KIND at PATH: autojump, z, fasd, zoxide,
+    /// csv, or a shell history with zsh-history, bash-history or fish-history
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["KIND", "PATH"],
+        conflicts_with_all = ["UpdateArgs", "StatsArgs", "batch", "stdin", "item"]
+    )]
+    pub import: Option<Vec<String>>,
+
+    /// Import files opened in an editor instead of directories changed to with cd
+    /// from a shell history
+    #[arg(long, requires = "import")]
+    pub files: bool,
+
+    /// Print the top N items after --import without writing the store
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        requires = "import"
+    )]
+    pub preview: Option<usize>,
+
+    /// The items to update
+    #[arg(group = "targets")]
+    pub item: Vec<String>,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Add a visit to ITEM
+    Add {
+        #[command(flatten)]
+        items: ItemArgs,
+
+        /// Add the visit as of TIME, in seconds since the epoch or as an RFC 3339 timestamp
+        #[arg(long, value_name = "TIME", value_parser = parse_timestamp)]
+        at: Option<f64>,
+
+        /// Set the kind of each ITEM instead of guessing it
+        #[arg(long, value_enum)]
+        kind: Option<Kind>,
+    },
+    /// Increase the weight of ITEM by WEIGHT, or decrease it if WEIGHT is negative
+    Adjust {
+        /// The amount to add to the weight, which may be negative
+        #[arg(allow_negative_numbers = true)]
+        weight: f64,
+
+        #[command(flatten)]
+        items: ItemArgs,
+
+        /// Adjust the weight as of TIME, in seconds since the epoch or as an RFC 3339 timestamp
+        #[arg(long, value_name = "TIME", value_parser = parse_timestamp)]
+        at: Option<f64>,
+
+        /// Set the kind of each ITEM instead of guessing it
+        #[arg(long, value_enum)]
+        kind: Option<Kind>,
+    },
+    /// Delete ITEM from the store
+    Delete {
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+    /// Print the stored items in order of highest to lowest score
+    List {
+        #[command(flatten)]
+        display: DisplayArgs,
+
+        /// Print the stored paths as a tree, with the total frecency and accesses below each node
+        #[arg(long, conflicts_with_all = ["relative", "rollup", "output", "format", "show"])]
+        tree: bool,
+
+        /// Only print tree nodes up to N levels deep
+        #[arg(long, value_name = "N", requires = "tree")]
+        depth: Option<usize>,
+
+        /// Only print tree nodes with a total frecency of at least SCORE
+        #[arg(long, value_name = "SCORE", requires = "tree")]
+        threshold: Option<f64>,
+    },
+    /// Print the stored items with their scores
+    Stat {
+        #[command(flatten)]
+        display: DisplayArgs,
+
+        /// Print the frecent score, number of accesses and hours since the last access side by side
+        #[arg(long, conflicts_with_all = ["output", "format"])]
+        all_scores: bool,
+
+        /// Do not print column headers with --all-scores
+        #[arg(long, requires = "all_scores")]
+        no_header: bool,
+
+        /// Print a footer with the totals of each column with --all-scores
+        #[arg(long, requires = "all_scores")]
+        totals: bool,
+
+        /// Override the number of digits shown for each score
+        #[arg(long)]
+        stat_digits: Option<usize>,
+    },
+    /// Remove items from the store. Pinned items are kept by --threshold and --missing
+    #[command(group = clap::ArgGroup::new("gc").required(true).multiple(true))]
+    Gc {
+        /// Keep only the top N items
+        #[arg(long, value_name = "N", group = "gc")]
+        keep: Option<usize>,
+
+        /// Remove items with a frecency lower than SCORE
+        #[arg(long, value_name = "SCORE", group = "gc")]
+        threshold: Option<f64>,
+
+        /// Remove items that are absolute paths which no longer exist
+        #[arg(long, group = "gc")]
+        missing: bool,
+    },
+    /// Show or change the store's settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// List, copy, rename or delete the namespaces in the store file
+    Ns {
+        #[command(subcommand)]
+        command: NsCommand,
+    },
+    /// Add or remove a tag on stored items
+    Tag {
+        #[command(subcommand)]
+        command: TagCommand,
+    },
+    /// Set or unset a metadata key on stored items
+    Meta {
+        #[command(subcommand)]
+        command: MetaCommand,
+    },
+    /// Set or unset the short alias of a stored item, which --best resolves exactly
+    Alias {
+        #[command(subcommand)]
+        command: AliasCommand,
+    },
+    /// Set or unset the label printed in place of stored items with --show
+    Label {
+        #[command(subcommand)]
+        command: LabelCommand,
+    },
+    /// Print the item with the alias QUERY, or else the highest scoring item matching QUERY
+    Best { query: String },
+    /// Print the stored statistics of ITEM, how its scores are calculated from them,
+    /// and when its frecency will decay below 1 and 0.1
+    Explain { item: String },
+    /// Set or unset the kind of stored items: dir, file, url, command or other
+    Kind {
+        #[command(subcommand)]
+        command: KindCommand,
+    },
+    /// Print a script to add directories to the store as they are visited in SHELL
+    Init {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Print a script to complete options and stored items in SHELL
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the effective settings, where each came from, and the number of items
+    Show,
+    /// Change the half life to SECONDS, keeping the current scores
+    Halflife {
+        #[arg(value_name = "SECONDS", value_parser = parse_half_life, allow_negative_numbers = true)]
+        half_life: f64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NsCommand {
+    /// Print the name of each namespace, starting with the default namespace
+    List,
+    /// Copy the namespace FROM, with its half life and items, to a new namespace TO
+    Copy { from: String, to: String },
+    /// Rename the namespace FROM to TO
+    Rename { from: String, to: String },
+    /// Delete the namespace NAME and all of its items
+    Delete { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TagCommand {
+    /// Add TAG to each ITEM
+    Add {
+        #[arg(value_parser = NonEmptyStringValueParser::new())]
+        tag: String,
+
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+    /// Remove TAG from each ITEM
+    Remove {
+        tag: String,
+
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MetaCommand {
+    /// Set KEY to VALUE on each ITEM
+    Set {
+        #[arg(value_name = "KEY=VALUE", value_parser = parse_key_value)]
+        pair: (String, String),
+
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+    /// Remove KEY from each ITEM
+    Unset {
+        key: String,
+
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AliasCommand {
+    /// Give ITEM the alias ALIAS, which no other item may have
+    Set {
+        #[arg(value_parser = NonEmptyStringValueParser::new())]
+        alias: String,
+
+        item: String,
+    },
+    /// Remove the alias of each ITEM
+    Unset {
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LabelCommand {
+    /// Set the label of each ITEM to LABEL
+    Set {
+        #[arg(value_parser = NonEmptyStringValueParser::new())]
+        label: String,
+
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+    /// Remove the label of each ITEM
+    Unset {
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum KindCommand {
+    /// Set the kind of each ITEM to KIND
+    Set {
+        #[arg(value_enum)]
+        kind: Kind,
+
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+    /// Forget the kind of each ITEM, so that it is guessed from the item again
+    Unset {
+        #[command(flatten)]
+        items: ItemArgs,
+    },
+}
+
+/// The items to update with a subcommand
 #[derive(Args, Debug)]
-#[group(multiple = false, conflicts_with = "StatsArgs")]
+pub struct ItemArgs {
+    /// The items to update
+    #[arg(required_unless_present = "stdin")]
+    pub item: Vec<String>,
+
+    /// Read the items to update from stdin, one per line
+    #[arg(long, conflicts_with = "item")]
+    pub stdin: bool,
+}
+
+impl ItemArgs {
+    fn items(&self) -> Items {
+        if self.stdin {
+            Items::Stdin
+        } else {
+            Items::Args(self.item.clone())
+        }
+    }
+}
+
+/// Options for printing the stored items with the list and stat subcommands
+#[derive(Args, Debug)]
+pub struct DisplayArgs {
+    /// Limit the number of items printed, or the number of children of each tree node
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Only print items that are paths inside DIR
+    #[arg(long, value_name = "DIR")]
+    pub under: Option<PathBuf>,
+
+    /// Print items relative to the directory given to --under
+    #[arg(long, requires = "under")]
+    pub relative: bool,
+
+    /// Add the scores of items to each of their ancestors inside the directory given to --under
+    #[arg(long, requires = "under")]
+    pub rollup: bool,
+
+    /// The format to print the items in (default text)
+    #[arg(long, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Print each item with a template like '{rank:>3} {frecency:.2} {last_access:relative} {item}'.
+    /// Fields are item, rank, score, frecency, accesses, last_access, age, hours, percent, pinned,
+    /// tags, meta.KEY, alias, label and kind
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        value_parser = Template::parse,
+        conflicts_with = "output"
+    )]
+    pub format: Option<Template>,
+
+    /// Only print items tagged with TAG. May be given more than once
+    #[arg(long, value_name = "TAG")]
+    pub tag: Vec<String>,
+
+    /// Only print items with metadata KEY set to VALUE. May be given more than once
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub meta: Vec<(String, String)>,
+
+    /// Print the item, its label, or both separated by a tab in text output (default item)
+    #[arg(long, value_enum, conflicts_with = "format")]
+    pub show: Option<Show>,
+
+    /// Only print items of KIND, guessing the kind of items that have none
+    #[arg(long, value_enum, value_name = "KIND")]
+    pub kind: Option<Kind>,
+}
+
+impl DisplayArgs {
+    fn list_options(&self, style: ListStyle) -> ListOptions {
+        ListOptions {
+            style,
+            limit: self.limit,
+            under: self.under.clone(),
+            relative: self.relative,
+            rollup: self.rollup,
+            output: self.output,
+            format: self.format.clone(),
+            all_scores: false,
+            header: true,
+            totals: false,
+            stat_digits: None,
+            tags: self.tag.clone(),
+            metadata: self.meta.clone(),
+            show: self.show.unwrap_or(Show::Item),
+            kind: self.kind,
+        }
+    }
+}
+
+impl Cli {
+    /// Parse the command line, exiting with a usage error if it is invalid
+    pub fn parse_command_line() -> Cli {
+        Cli::try_parse_command_line(std::env::args_os()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse `args` like `try_parse_from`, except that the items given to `--add`, `--delete`,
+    /// `--increase` or `--decrease` are never taken for a subcommand, so `fre --add list`
+    /// adds the item `list`
+    pub fn try_parse_command_line<I, T>(args: I) -> Result<Cli, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        let update = Cli::command()
+            .args_conflicts_with_subcommands(true)
+            .try_get_matches_from(&args)
+            .ok()
+            .and_then(|matches| Cli::from_arg_matches(&matches).ok())
+            .filter(|cli| {
+                let updates = &cli.updates;
+                updates.add
+                    || updates.delete
+                    || updates.increase.is_some()
+                    || updates.decrease.is_some()
+            });
+        match update {
+            Some(cli) => Ok(cli),
+            None => Cli::try_parse_from(args),
+        }
+    }
+
+    /// The store file given with `--store` or `--store_name`
+    pub fn store_file(&self) -> Option<StoreFile> {
+        match (&self.store, &self.store_name) {
+            (Some(path), _) => Some(StoreFile::Path(path.clone())),
+            (None, Some(name)) => Some(StoreFile::Name(name.clone())),
+            (None, None) => None,
+        }
+    }
+
+    /// The operation given by the subcommand, or by the flags if there is none
+    pub fn operation(&self) -> Operation {
+        match &self.command {
+            Some(command) => command.operation(),
+            None => self.flag_operation(),
+        }
+    }
+
+    /// The operation given by the flags
+    fn flag_operation(&self) -> Operation {
+        let stats = &self.stats;
+        if stats.pick {
+            return Operation::Pick { limit: stats.limit };
+        }
+        if let Some(query) = &stats.best {
+            return Operation::Best(query.clone());
+        }
+        if stats.sorted || stats.stat || stats.tree {
+            let style = if stats.tree {
+                ListStyle::Tree {
+                    depth: stats.depth,
+                    threshold: stats.threshold,
+                }
+            } else if stats.stat {
+                ListStyle::Stat
+            } else {
+                ListStyle::Sorted
+            };
+            return Operation::List(ListOptions {
+                style,
+                limit: stats.limit,
+                under: stats.under.clone(),
+                relative: stats.relative,
+                rollup: stats.rollup,
+                output: stats.output,
+                format: stats.format.clone(),
+                all_scores: stats.all_scores,
+                header: !stats.no_header,
+                totals: stats.totals,
+                stat_digits: stats.stat_digits,
+                tags: stats.tag.clone(),
+                metadata: stats.meta.clone(),
+                show: stats.show.unwrap_or(Show::Item),
+                kind: self.kind,
+            });
+        }
+
+        let updates = &self.updates;
+        let update = match (
+            updates.add,
+            updates.increase,
+            updates.decrease,
+            updates.delete,
+        ) {
+            (true, _, _, _) => Some(Update::Add),
+            (_, Some(i), _, _) => Some(Update::Adjust(i)),
+            (_, _, Some(d), _) => Some(Update::Adjust(-d)),
+            (_, _, _, true) => Some(Update::Delete),
+            _ => None,
+        };
+        if let Some(update) = update {
+            let items = if self.stdin {
+                Items::Stdin
+            } else {
+                Items::Args(self.item.clone())
+            };
+            return Operation::Update {
+                update,
+                items,
+                at: self.at,
+                kind: self.kind,
+            };
+        }
+
+        if self.batch {
+            Operation::Batch
+        } else if let Some(import) = &self.import {
+            Operation::Import {
+                kind: import[0].clone(),
+                path: PathBuf::from(&import[1]),
+                files: self.files,
+                preview: self.preview,
+            }
+        } else if let Some(kind) = self.export {
+            Operation::Export(kind)
+        } else if let Some(item) = &self.explain {
+            Operation::Explain(item.clone())
+        } else {
+            Operation::None
+        }
+    }
+
+    /// The character that separates items in input and output
+    pub fn terminator(&self) -> char {
+        if self.null {
+            '\0'
+        } else {
+            '\n'
+        }
+    }
+}
+
+impl Command {
+    fn operation(&self) -> Operation {
+        match self {
+            Command::Add { items, at, kind } => Operation::Update {
+                update: Update::Add,
+                items: items.items(),
+                at: *at,
+                kind: *kind,
+            },
+            Command::Adjust {
+                weight,
+                items,
+                at,
+                kind,
+            } => Operation::Update {
+                update: Update::Adjust(*weight),
+                items: items.items(),
+                at: *at,
+                kind: *kind,
+            },
+            Command::Delete { items } => Operation::Update {
+                update: Update::Delete,
+                items: items.items(),
+                at: None,
+                kind: None,
+            },
+            Command::List {
+                display,
+                tree,
+                depth,
+                threshold,
+            } => Operation::List(display.list_options(if *tree {
+                ListStyle::Tree {
+                    depth: *depth,
+                    threshold: *threshold,
+                }
+            } else {
+                ListStyle::Sorted
+            })),
+            Command::Stat {
+                display,
+                all_scores,
+                no_header,
+                totals,
+                stat_digits,
+            } => Operation::List(ListOptions {
+                all_scores: *all_scores,
+                header: !no_header,
+                totals: *totals,
+                stat_digits: *stat_digits,
+                ..display.list_options(ListStyle::Stat)
+            }),
+            Command::Gc {
+                keep,
+                threshold,
+                missing,
+            } => Operation::Gc(GcOptions {
+                keep: *keep,
+                threshold: *threshold,
+                missing: *missing,
+            }),
+            Command::Config {
+                command: ConfigCommand::Show,
+            } => Operation::ShowConfig,
+            Command::Config {
+                command: ConfigCommand::Halflife { half_life },
+            } => Operation::SetHalfLife(*half_life),
+            Command::Ns { command } => Operation::Namespace(match command {
+                NsCommand::List => NamespaceOperation::List,
+                NsCommand::Copy { from, to } => NamespaceOperation::Copy {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+                NsCommand::Rename { from, to } => NamespaceOperation::Rename {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+                NsCommand::Delete { name } => NamespaceOperation::Delete(name.clone()),
+            }),
+            Command::Tag { command } => match command {
+                TagCommand::Add { tag, items } => Operation::Annotate {
+                    annotation: Annotation::AddTag(tag.clone()),
+                    items: items.items(),
+                },
+                TagCommand::Remove { tag, items } => Operation::Annotate {
+                    annotation: Annotation::RemoveTag(tag.clone()),
+                    items: items.items(),
+                },
+            },
+            Command::Meta { command } => match command {
+                MetaCommand::Set {
+                    pair: (key, value),
+                    items,
+                } => Operation::Annotate {
+                    annotation: Annotation::SetMeta(key.clone(), value.clone()),
+                    items: items.items(),
+                },
+                MetaCommand::Unset { key, items } => Operation::Annotate {
+                    annotation: Annotation::UnsetMeta(key.clone()),
+                    items: items.items(),
+                },
+            },
+            Command::Alias { command } => match command {
+                AliasCommand::Set { alias, item } => Operation::Annotate {
+                    annotation: Annotation::SetAlias(alias.clone()),
+                    items: Items::Args(vec![item.clone()]),
+                },
+                AliasCommand::Unset { items } => Operation::Annotate {
+                    annotation: Annotation::UnsetAlias,
+                    items: items.items(),
+                },
+            },
+            Command::Label { command } => match command {
+                LabelCommand::Set { label, items } => Operation::Annotate {
+                    annotation: Annotation::SetLabel(label.clone()),
+                    items: items.items(),
+                },
+                LabelCommand::Unset { items } => Operation::Annotate {
+                    annotation: Annotation::UnsetLabel,
+                    items: items.items(),
+                },
+            },
+            Command::Best { query } => Operation::Best(query.clone()),
+            Command::Explain { item } => Operation::Explain(item.clone()),
+            Command::Kind { command } => match command {
+                KindCommand::Set { kind, items } => Operation::Annotate {
+                    annotation: Annotation::SetKind(*kind),
+                    items: items.items(),
+                },
+                KindCommand::Unset { items } => Operation::Annotate {
+                    annotation: Annotation::UnsetKind,
+                    items: items.items(),
+                },
+            },
+            // Handled before the store is read
+            Command::Init { .. } | Command::Completions { .. } => Operation::None,
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+#[group(multiple = false, conflicts_with = "StatsArgs", requires = "targets")]
 pub struct UpdateArgs {
     /// Add a visit to ITEM to the store
     #[arg(short = 'a', long)]
@@ -63,36 +799,151 @@ pub struct StatsArgs {
     #[arg(long, group = "list")]
     pub stat: bool,
 
-    /// Limit the number of results printed with --sorted or --stat
-    #[arg(long, requires = "list")]
+    /// Print the stored paths as a tree, with the total frecency and accesses below each node
+    #[arg(long, group = "list")]
+    pub tree: bool,
+
+    /// Interactively pick an item, printing the chosen item
+    #[arg(long, group = "list")]
+    pub pick: bool,
+
+    /// Print the item with the alias QUERY, or else the highest scoring item matching QUERY
+    #[arg(long, value_name = "QUERY", group = "list")]
+    pub best: Option<String>,
+
+    /// Limit the number of results printed with --sorted, --stat or --pick,
+    /// or the number of children of each node printed with --tree
+    #[arg(long, requires = "list", conflicts_with = "best")]
     pub limit: Option<usize>,
 
+    /// Only print items that are paths inside DIR
+    #[arg(
+        long,
+        value_name = "DIR",
+        requires = "list",
+        conflicts_with_all = ["pick", "best"]
+    )]
+    pub under: Option<PathBuf>,
+
+    /// Print items relative to the directory given to --under
+    #[arg(long, requires = "under", conflicts_with = "tree")]
+    pub relative: bool,
+
+    /// Add the scores of items to each of their ancestors inside the directory given to --under
+    #[arg(long, requires = "under", conflicts_with = "tree")]
+    pub rollup: bool,
+
+    /// Only print tree nodes up to N levels deep
+    #[arg(long, value_name = "N", requires = "tree")]
+    pub depth: Option<usize>,
+
+    /// Only print tree nodes with a total frecency of at least SCORE
+    #[arg(long, value_name = "SCORE", requires = "tree")]
+    pub threshold: Option<f64>,
+
+    /// The format to print --sorted or --stat output in (default text)
+    #[arg(long, value_enum, conflicts_with_all = ["tree", "pick", "best"])]
+    pub output: Option<OutputFormat>,
+
+    /// Print each item with a template like '{rank:>3} {frecency:.2} {last_access:relative} {item}'.
+    /// Fields are item, rank, score, frecency, accesses, last_access, age, hours, percent, pinned,
+    /// tags, meta.KEY, alias, label and kind
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        value_parser = Template::parse,
+        requires = "list",
+        conflicts_with_all = ["output", "tree", "pick", "best"],
+    )]
+    pub format: Option<Template>,
+
+    /// Print the frecent score, number of accesses and hours since the last access side by side
+    #[arg(long, requires = "stat", conflicts_with_all = ["output", "format"])]
+    pub all_scores: bool,
+
+    /// Do not print column headers with --all-scores
+    #[arg(long, requires = "all_scores")]
+    pub no_header: bool,
+
+    /// Print a footer with the totals of each column with --all-scores
+    #[arg(long, requires = "all_scores")]
+    pub totals: bool,
+
     /// Override the number of digits shown with --stat
     #[arg(long, requires = "stat")]
     pub stat_digits: Option<usize>,
+
+    /// Only print items tagged with TAG with --sorted, --stat or --tree.
+    /// May be given more than once
+    #[arg(
+        long,
+        value_name = "TAG",
+        requires = "list",
+        conflicts_with_all = ["pick", "best"]
+    )]
+    pub tag: Vec<String>,
+
+    /// Only print items with metadata KEY set to VALUE with --sorted, --stat or --tree.
+    /// May be given more than once
+    #[arg(
+        long,
+        value_name = "KEY=VALUE",
+        value_parser = parse_key_value,
+        requires = "list",
+        conflicts_with_all = ["pick", "best"]
+    )]
+    pub meta: Vec<(String, String)>,
+
+    /// Print the item, its label, or both separated by a tab with --sorted or --stat
+    /// in text output (default item)
+    #[arg(
+        long,
+        value_enum,
+        requires = "list",
+        conflicts_with_all = ["tree", "pick", "best", "format"]
+    )]
+    pub show: Option<Show>,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortMethod {
     Recent,
     Frequent,
     Frecent,
 }
 
-impl From<SortMethod> for OsStr {
-    fn from(value: SortMethod) -> Self {
-        match value {
-            SortMethod::Recent => OsStr::from("recent"),
-            SortMethod::Frequent => OsStr::from("frequent"),
-            SortMethod::Frecent => OsStr::from("frecent"),
-        }
-    }
+/// What to print for each item in text output
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Show {
+    /// The item itself
+    Item,
+    /// The item's label, or the item if it has no label
+    Label,
+    /// The label and the item, separated by a tab
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// One item per line, preceded by its score with --stat
+    Text,
+    /// A JSON array of objects describing each item
+    Json,
+    /// One JSON object describing each item per line
+    Jsonl,
 }
 
 #[derive(Args, Debug)]
 pub struct JanitorArgs {
     /// Change the halflife to N seconds (default 3 days)
-    #[arg(long, value_name = "N")]
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = parse_half_life,
+        allow_negative_numbers = true
+    )]
     pub halflife: Option<f64>,
 
     /// Truncate the stored items to only the top N
@@ -100,12 +951,43 @@ pub struct JanitorArgs {
     pub truncate: Option<usize>,
 }
 
-/// Given the argument matches, return the path of the store file.
-pub fn get_store_path(args: &Cli) -> Result<PathBuf> {
-    match (&args.store, &args.store_name) {
-        (Some(dir), None) => Ok(dir.to_owned()),
-        (None, filename) => default_store(filename.to_owned()),
-        _ => unreachable!(),
+/// Parse a `KEY=VALUE` pair of item metadata
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{}'", s)),
+    }
+}
+
+/// Parse a half life in seconds, which must be valid by `is_valid_half_life`
+pub fn parse_half_life(s: &str) -> Result<f64, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|h| is_valid_half_life(*h))
+        .ok_or_else(|| format!("expected a positive number of seconds, got '{}'", s))
+}
+
+/// Whether a half life is finite and positive, as it must be for scores to decay
+pub fn is_valid_half_life(half_life: f64) -> bool {
+    half_life.is_finite() && half_life > 0.0
+}
+
+/// A store file given as a path, or by its name in the default store directory
+pub enum StoreFile {
+    /// The path of the store file, as given to `--store` or `FRE_STORE`
+    Path(PathBuf),
+    /// The name of a file in the default store directory, or an absolute path, as given to
+    /// `--store_name` or in the config file
+    Name(PathBuf),
+}
+
+/// Return the path of the store file `store`, or of the default store file if it is `None`.
+pub fn get_store_path(store: Option<StoreFile>) -> Result<PathBuf> {
+    match store {
+        Some(StoreFile::Path(path)) => Ok(path),
+        Some(StoreFile::Name(name)) if name.is_absolute() => Ok(name),
+        Some(StoreFile::Name(name)) => default_store(Some(name)),
+        None => default_store(None),
     }
 }
 
@@ -134,7 +1016,7 @@ mod tests {
         let arg_vec = vec!["fre", "--store", "/test/path"];
         let args = Cli::try_parse_from(arg_vec).unwrap();
 
-        let store_path = get_store_path(&args).unwrap();
+        let store_path = get_store_path(args.store_file()).unwrap();
 
         assert_eq!(PathBuf::from("/test/path"), store_path);
     }
@@ -144,7 +1026,7 @@ mod tests {
         let arg_vec = vec!["fre", "--store_name", "test.path"];
         let args = Cli::try_parse_from(arg_vec).unwrap();
 
-        let store_path = get_store_path(&args).unwrap();
+        let store_path = get_store_path(args.store_file()).unwrap();
 
         assert_eq!(
             store_path
@@ -155,4 +1037,62 @@ mod tests {
             "test.path".to_string()
         );
     }
+
+    fn operation(arg_vec: &[&str]) -> Operation {
+        Cli::try_parse_from(arg_vec).unwrap().operation()
+    }
+
+    #[test]
+    fn subcommands_match_flags() {
+        assert_eq!(
+            operation(&["fre", "--store", "/s", "add", "/a"]),
+            operation(&["fre", "--add", "/a"])
+        );
+        assert_eq!(
+            operation(&["fre", "adjust", "-1.5", "/a"]),
+            operation(&["fre", "--decrease", "1.5", "/a"])
+        );
+        assert_eq!(
+            operation(&["fre", "delete", "--stdin"]),
+            operation(&["fre", "--delete", "--stdin"])
+        );
+        assert_eq!(
+            operation(&["fre", "delete", "/a", "/b"]),
+            operation(&["fre", "--delete", "/a", "/b"])
+        );
+        assert_eq!(
+            operation(&["fre", "stat", "--limit", "2", "--stat-digits", "1"]),
+            operation(&["fre", "--stat", "--limit", "2", "--stat-digits", "1"])
+        );
+        assert_eq!(
+            operation(&["fre", "list", "--tree", "--depth", "2"]),
+            operation(&["fre", "--tree", "--depth", "2"])
+        );
+    }
+
+    #[test]
+    fn items_named_like_subcommands() {
+        let args = Cli::try_parse_command_line(["fre", "--add", "list", "stat"]).unwrap();
+        assert!(args.command.is_none());
+        assert_eq!(args.item, ["list", "stat"]);
+
+        let args = Cli::try_parse_command_line(["fre", "--store", "/s", "-i", "2", "gc"]).unwrap();
+        assert!(args.command.is_none());
+        assert_eq!(args.item, ["gc"]);
+
+        let args = Cli::try_parse_command_line(["fre", "--store", "/s", "list"]).unwrap();
+        assert!(matches!(args.command, Some(Command::List { .. })));
+
+        let args = Cli::try_parse_command_line(["fre", "--ns", "work", "add", "/a"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Add { .. })));
+    }
+
+    #[test]
+    fn subcommand_validation() {
+        assert!(Cli::try_parse_from(["fre", "add"]).is_err());
+        assert!(Cli::try_parse_from(["fre", "adjust", "/a"]).is_err());
+        assert!(Cli::try_parse_from(["fre", "gc"]).is_err());
+        assert!(Cli::try_parse_from(["fre", "list", "--depth", "2"]).is_err());
+        assert!(Cli::try_parse_from(["fre", "stat", "--tree"]).is_err());
+    }
 }
@@ -0,0 +1,242 @@
+use super::store::FrecencyStore;
+use super::timestamp::parse_timestamp;
+use anyhow::{anyhow, bail, Result};
+use std::io::BufRead;
+
+/// A single operation read by `--batch`. Each line has one of the forms
+///
+/// ```text
+/// add [@TIME] ITEM
+/// adjust [@TIME] ITEM WEIGHT
+/// delete ITEM
+/// ```
+///
+/// where `TIME` is the time the operation happened at, defaulting to now.
+/// Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, PartialEq)]
+pub enum Operation {
+    Add {
+        item: String,
+        time: Option<f64>,
+    },
+    Adjust {
+        item: String,
+        weight: f64,
+        time: Option<f64>,
+    },
+    Delete {
+        item: String,
+    },
+}
+
+impl Operation {
+    /// The item the operation applies to
+    pub fn item(&self) -> &str {
+        match self {
+            Operation::Add { item, .. }
+            | Operation::Adjust { item, .. }
+            | Operation::Delete { item } => item,
+        }
+    }
+
+    pub fn item_mut(&mut self) -> &mut String {
+        match self {
+            Operation::Add { item, .. }
+            | Operation::Adjust { item, .. }
+            | Operation::Delete { item } => item,
+        }
+    }
+
+    /// Parse a single line of batch input
+    pub fn parse(line: &str) -> Result<Operation> {
+        let (op, args) = line.split_once(' ').unwrap_or((line, ""));
+        let (time, args) = match args.strip_prefix('@') {
+            Some(rest) if op != "delete" => {
+                let (time, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+                (Some(parse_timestamp(time)?), rest)
+            }
+            _ => (None, args),
+        };
+        if args.is_empty() {
+            bail!("{} requires an item", op);
+        }
+
+        Ok(match op {
+            "add" => Operation::Add {
+                item: args.to_string(),
+                time,
+            },
+            "adjust" => {
+                let (item, weight) = args
+                    .rsplit_once(' ')
+                    .ok_or_else(|| anyhow!("adjust requires an item and a weight"))?;
+                let weight = weight
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|w| w.is_finite())
+                    .ok_or_else(|| anyhow!("invalid weight '{}'", weight))?;
+                Operation::Adjust {
+                    item: item.to_string(),
+                    weight,
+                    time,
+                }
+            }
+            "delete" => Operation::Delete {
+                item: args.to_string(),
+            },
+            _ => bail!("unknown operation '{}'", op),
+        })
+    }
+
+    /// Apply the operation to `store`
+    pub fn apply(&self, store: &mut FrecencyStore) -> Result<()> {
+        match self {
+            Operation::Add { item, time: None } => store.add(item),
+            Operation::Add {
+                item,
+                time: Some(t),
+            } => store.add_at(item, *t),
+            Operation::Adjust {
+                item,
+                weight,
+                time: None,
+            } => store.adjust(item, *weight),
+            Operation::Adjust {
+                item,
+                weight,
+                time: Some(t),
+            } => store.adjust_at(item, *weight, *t),
+            Operation::Delete { item } => {
+                store.delete(item);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Read every operation from `reader`, with lines separated by `terminator`, along with
+/// the number of the line it was read from.
+/// If any line is invalid, return an error listing every invalid line by number.
+pub fn read_operations<R: BufRead>(reader: R, terminator: u8) -> Result<Vec<(usize, Operation)>> {
+    let mut operations = Vec::new();
+    let mut errors = Vec::new();
+
+    for (idx, line) in reader.split(terminator).enumerate() {
+        let line_num = idx + 1;
+        let line = match String::from_utf8(line?) {
+            Ok(line) => line,
+            Err(_) => {
+                errors.push(format!("line {}: not valid UTF-8", line_num));
+                continue;
+            }
+        };
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match Operation::parse(&line) {
+            Ok(op) => operations.push((line_num, op)),
+            Err(e) => errors.push(format!("line {}: {}", line_num, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("invalid batch input\n{}", errors.join("\n"));
+    }
+
+    Ok(operations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_add() {
+        assert_eq!(
+            Operation::parse("add /home/user/with space").unwrap(),
+            Operation::Add {
+                item: "/home/user/with space".to_string(),
+                time: None
+            }
+        );
+        assert_eq!(
+            Operation::parse("add @1703721600 /home").unwrap(),
+            Operation::Add {
+                item: "/home".to_string(),
+                time: Some(1703721600.0)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_adjust() {
+        assert_eq!(
+            Operation::parse("adjust /home/a b -2.5").unwrap(),
+            Operation::Adjust {
+                item: "/home/a b".to_string(),
+                weight: -2.5,
+                time: None
+            }
+        );
+        assert_eq!(
+            Operation::parse("adjust @10 /home 3").unwrap(),
+            Operation::Adjust {
+                item: "/home".to_string(),
+                weight: 3.0,
+                time: Some(10.0)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_delete() {
+        assert_eq!(
+            Operation::parse("delete @home").unwrap(),
+            Operation::Delete {
+                item: "@home".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Operation::parse("remove /home").is_err());
+        assert!(Operation::parse("add").is_err());
+        assert!(Operation::parse("add @notatime /home").is_err());
+        assert!(Operation::parse("adjust /home").is_err());
+        assert!(Operation::parse("adjust /home lots").is_err());
+        assert!(Operation::parse("adjust /home inf").is_err());
+        assert!(Operation::parse("add @4102444800 /home").is_err());
+    }
+
+    #[test]
+    fn read_with_errors() {
+        let input = "add /home\n# comment\n\nfrob /home\nadjust /home x\n".as_bytes();
+
+        let err = read_operations(input, b'\n').unwrap_err().to_string();
+
+        assert_eq!(
+            err,
+            "invalid batch input\nline 4: unknown operation 'frob'\nline 5: invalid weight 'x'"
+        );
+    }
+
+    #[test]
+    fn read_and_apply() {
+        let input = "add /a\0add /b\nwith newline\0adjust /a 2\0delete /c\0".as_bytes();
+        let mut store = FrecencyStore::default();
+        store.add("/c").unwrap();
+
+        for (_, op) in read_operations(input, b'\0').unwrap() {
+            op.apply(&mut store).unwrap();
+        }
+
+        let items: Vec<(&str, i32)> = store
+            .items
+            .iter()
+            .map(|i| (i.item.as_str(), i.num_accesses))
+            .collect();
+        assert_eq!(items, vec![("/a", 3), ("/b\nwith newline", 1)]);
+    }
+}
@@ -0,0 +1,60 @@
+
+# Complete ITEM with the stored items, ranked by frecency, after --delete, --increase or --decrease
+# and after the subcommands taking stored items, once their other arguments are given
+_fre_items() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" word item
+    local sub="" skip=0 count=0 last="" items=""
+    case "$prev" in
+        -i|--increase|-d|--decrease)
+            _fre "$@"
+            return
+            ;;
+    esac
+    if [[ "$cur" != -* ]]; then
+        for word in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+            if [[ -n "$sub" ]]; then
+                # Count the arguments before the items, such as the weight or the tag
+                if [[ "$last" != --at && "$last" != --kind ]] &&
+                    [[ "$word" != -* || "$word" == -[0-9.]* ]]; then
+                    (( count++ ))
+                    [[ $count -eq 1 && "$word" == unset && "$sub" != meta ]] && skip=1
+                fi
+            else
+                case "$word" in
+                    -D|--delete|-i|--increase|-d|--decrease)
+                        items=1
+                        break
+                        ;;
+                    add|delete|explain)
+                        sub="$word" skip=0
+                        ;;
+                    adjust)
+                        sub="$word" skip=1
+                        ;;
+                    tag|meta|alias|label|kind)
+                        sub="$word" skip=2
+                        ;;
+                    init|completions|help|list|stat|gc|config|ns|best)
+                        break
+                        ;;
+                esac
+            fi
+            last="$word"
+        done
+        [[ -n "$sub" ]] && (( count >= skip )) && items=1
+    fi
+    if [[ -n "$items" ]]; then
+        COMPREPLY=()
+        while IFS= read -r item; do
+            [[ "$item" == "$cur"* ]] && COMPREPLY+=("$(printf '%q' "$item")")
+        done < <(command fre --sorted 2>/dev/null)
+        return
+    fi
+    _fre "$@"
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _fre_items -o nosort -o bashdefault -o default fre
+else
+    complete -F _fre_items -o bashdefault -o default fre
+fi
@@ -0,0 +1,44 @@
+
+# Whether the word being completed is an item to delete, increase or decrease, or a stored item
+# given to a subcommand after its other arguments
+function __fre_completes_items
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    set -l sub
+    set -l skip 0
+    set -l count 0
+    set -l last
+    for token in $tokens
+        if set -q sub[1]
+            # Count the arguments before the items, such as the weight or the tag
+            if not contains -- "$last" --at --kind; and string match -qr -- '^([^-]|-[0-9.])' $token
+                set count (math $count + 1)
+                if test $count -eq 1 -a "$token" = unset -a "$sub" != meta
+                    set skip 1
+                end
+            end
+        else
+            switch $token
+                case -D --delete -i --increase -d --decrease
+                    return 0
+                case add delete explain
+                    set sub $token
+                    set skip 0
+                case adjust
+                    set sub $token
+                    set skip 1
+                case tag meta alias label kind
+                    set sub $token
+                    set skip 2
+                case init completions help list stat gc config ns best
+                    return 1
+            end
+        end
+        set last $token
+    end
+    set -q sub[1]; and test $count -ge $skip
+end
+
+# Complete ITEM with the stored items, ranked by frecency, still offering files to add
+complete -c fre -n "__fre_completes_items; and not __fish_prev_arg_in -i --increase -d --decrease; and not __fish_seen_subcommand_from add" -f -k -a "(command fre --sorted 2>/dev/null)"
+complete -c fre -n "__fre_completes_items; and __fish_seen_subcommand_from add" -k -a "(command fre --sorted 2>/dev/null)"
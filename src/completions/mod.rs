@@ -0,0 +1,88 @@
+use crate::args::Cli;
+use anyhow::{anyhow, Result};
+use clap::CommandFactory;
+use clap_complete::Shell;
+use std::io::Write;
+
+/// The end of the argument specification clap_complete generates for ITEM in zsh, and the
+/// same specification completing stored items
+const ZSH_ITEM_SPEC: &str = "::item -- The items to update:'";
+const ZSH_ITEM_COMPLETION: &str = "::item -- The items to update:_fre_items'";
+
+/// The specification clap_complete generates for the single ITEM of `explain` and `alias set`
+/// in zsh, and the same specification completing stored items
+const ZSH_SINGLE_ITEM_SPEC: &str = "':item:'";
+const ZSH_SINGLE_ITEM_COMPLETION: &str = "':item:_fre_items'";
+
+/// The line that ends the zsh completion script by running or registering `_fre`
+const ZSH_SCRIPT_END: &str = "if [ \"$funcstack[1]\" = \"_fre\" ]; then";
+
+/// Print a completion script for `shell`. In bash, zsh and fish, the item given to
+/// --delete, --increase or --decrease or to a subcommand taking stored items is completed
+/// with the stored items ranked by frecency.
+pub fn write_completions<W: Write>(w: &mut W, shell: Shell) -> Result<()> {
+    let mut script = Vec::new();
+    clap_complete::generate(
+        shell,
+        &mut Cli::command(),
+        env!("CARGO_PKG_NAME"),
+        &mut script,
+    );
+    let script = String::from_utf8(script)?;
+
+    match shell {
+        Shell::Bash => {
+            w.write_all(script.as_bytes())?;
+            w.write_all(include_str!("items.bash").as_bytes())?;
+        }
+        Shell::Zsh => {
+            // The item function must be defined before the script calls `_fre`
+            let end = script
+                .find(ZSH_SCRIPT_END)
+                .ok_or_else(|| anyhow!("unexpected zsh completion script"))?;
+            let (body, tail) = script.split_at(end);
+            let body = body
+                .replace(ZSH_ITEM_SPEC, ZSH_ITEM_COMPLETION)
+                .replace(ZSH_SINGLE_ITEM_SPEC, ZSH_SINGLE_ITEM_COMPLETION);
+            w.write_all(body.as_bytes())?;
+            w.write_all(include_str!("items.zsh").as_bytes())?;
+            w.write_all(tail.as_bytes())?;
+        }
+        Shell::Fish => {
+            w.write_all(script.as_bytes())?;
+            w.write_all(include_str!("items.fish").as_bytes())?;
+        }
+        _ => w.write_all(script.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completions(shell: Shell) -> String {
+        let mut b = Vec::new();
+        write_completions(&mut b, shell).unwrap();
+        String::from_utf8(b).unwrap()
+    }
+
+    #[test]
+    fn zsh_completes_items() {
+        let script = completions(Shell::Zsh);
+
+        assert!(script.contains(&format!("'{}", ZSH_ITEM_COMPLETION)));
+        assert!(script.contains(&format!("'*{}", ZSH_ITEM_COMPLETION)));
+        assert!(!script.contains(ZSH_ITEM_SPEC));
+        assert!(script.contains(ZSH_SINGLE_ITEM_COMPLETION));
+        assert!(!script.contains(ZSH_SINGLE_ITEM_SPEC));
+        assert!(script.find("_fre_items() {").unwrap() < script.find(ZSH_SCRIPT_END).unwrap());
+    }
+
+    #[test]
+    fn bash_and_fish_complete_items() {
+        assert!(completions(Shell::Bash).contains("complete -F _fre_items"));
+        assert!(completions(Shell::Fish).contains("-a \"(command fre --sorted 2>/dev/null)\""));
+    }
+}
@@ -0,0 +1,704 @@
+use crate::args::{self, Cli, OutputFormat, SortMethod, StoreFile};
+use crate::operation::{ListOptions, ListStyle};
+use crate::stats::Kind;
+use crate::store::DEFAULT_NAMESPACE;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use directories::{BaseDirs, ProjectDirs};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// The settings that can be given at the top level of the config file or in a profile.
+/// Any setting left out falls back to the next layer down.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    /// The store file, or the name of a file in the default store directory
+    pub store: Option<PathBuf>,
+    /// The namespace in the store file
+    pub namespace: Option<String>,
+    pub half_life: Option<f64>,
+    pub sort_method: Option<SortMethod>,
+    pub stat_digits: Option<usize>,
+    pub limit: Option<usize>,
+    /// Patterns of items that are never added, where `*` matches any run of characters
+    pub ignore: Option<Vec<String>>,
+    /// Resolve items that are existing paths to their canonical form before updating them
+    pub canonicalize: Option<bool>,
+    pub output: Option<OutputFormat>,
+}
+
+/// The contents of a config file: top-level settings and named profiles that override them
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigFile {
+    pub settings: Settings,
+    pub profiles: BTreeMap<String, Settings>,
+}
+
+impl ConfigFile {
+    /// Parse the TOML contents of a config file
+    pub fn parse(contents: &str) -> Result<ConfigFile> {
+        let mut table: toml::Table = contents.parse()?;
+        let profiles: BTreeMap<String, Settings> = match table.remove("profiles") {
+            Some(profiles) => profiles.try_into()?,
+            None => BTreeMap::new(),
+        };
+        let settings: Settings = toml::Value::Table(table).try_into()?;
+
+        settings.check("half_life")?;
+        for (name, profile) in &profiles {
+            profile.check(&format!("half_life in profile {}", name))?;
+        }
+
+        Ok(ConfigFile { settings, profiles })
+    }
+}
+
+impl Settings {
+    /// Check that the values deserialized from TOML are in range, naming the half life `key`
+    fn check(&self, key: &str) -> Result<()> {
+        match self.half_life {
+            Some(h) if !args::is_valid_half_life(h) => {
+                bail!("invalid value '{}' for {}", h, key)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Where the effective value of a setting came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Default,
+    StoreFile,
+    ConfigFile,
+    Profile(String),
+    Environment(&'static str),
+    CommandLine,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::StoreFile => write!(f, "store file"),
+            Source::ConfigFile => write!(f, "config file"),
+            Source::Profile(name) => write!(f, "profile {}", name),
+            Source::Environment(name) => write!(f, "environment {}", name),
+            Source::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// The effective value of a setting and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Setting<T> {
+    fn new(value: T, source: Source) -> Self {
+        Setting { value, source }
+    }
+}
+
+/// The effective settings after layering the command line over the profile selected with
+/// `--profile`, the environment, the top level of the config file and the defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// The config file that was looked for, whether or not it exists
+    pub path: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub store: Setting<PathBuf>,
+    pub namespace: Setting<String>,
+    /// The half life to set on the store, or `None` to keep the store's own
+    pub half_life: Setting<Option<f64>>,
+    pub sort_method: Setting<SortMethod>,
+    pub stat_digits: Setting<Option<usize>>,
+    pub limit: Setting<Option<usize>>,
+    pub ignore: Setting<Vec<String>>,
+    pub canonicalize: Setting<bool>,
+    pub output: Setting<OutputFormat>,
+    /// Whether recording visits is turned off with FRE_DISABLE
+    pub disabled: Setting<bool>,
+}
+
+/// The layers of settings below the command line, from highest precedence: the profile
+/// selected with `--profile`, the environment and the top level of the config file
+struct Layers<'a> {
+    profile: Option<(&'a Settings, Source)>,
+    file: &'a Settings,
+}
+
+impl<'a> Layers<'a> {
+    /// The value given on the command line by `flag`, or else by the first layer that sets it,
+    /// where `env` is the value of the setting's environment variable
+    fn get<T>(
+        &self,
+        flag: Option<T>,
+        env: Option<Setting<T>>,
+        setting: impl Fn(&Settings) -> Option<T>,
+    ) -> Option<Setting<T>> {
+        let profile = self
+            .profile
+            .as_ref()
+            .and_then(|(settings, source)| Some(Setting::new(setting(settings)?, source.clone())));
+        flag.map(|value| Setting::new(value, Source::CommandLine))
+            .or(profile)
+            .or(env)
+            .or_else(|| Some(Setting::new(setting(self.file)?, Source::ConfigFile)))
+    }
+}
+
+/// Read the environment variable `name` with `parse`, treating an empty value as unset
+fn env_var<T>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Option<Setting<T>>> {
+    match env(name).filter(|value| !value.is_empty()) {
+        Some(value) => match parse(&value) {
+            Some(parsed) => Ok(Some(Setting::new(parsed, Source::Environment(name)))),
+            None => bail!("invalid value '{}' for {}", value, name),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Parse a boolean environment variable, which is `1`, `true` or `yes`, or `0`, `false` or `no`
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+impl Config {
+    /// Read the config file from the default config directory, if it exists, and combine it
+    /// with the command line
+    pub fn load(cli: &Cli) -> Result<Config> {
+        let path = ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+            .map(|dirs| dirs.config_dir().join("config.toml"));
+
+        let file = match &path {
+            Some(path) => match fs::read_to_string(path) {
+                Ok(contents) => ConfigFile::parse(&contents)
+                    .with_context(|| format!("failed to read config file {:?}", path))?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => ConfigFile::default(),
+                Err(e) => {
+                    return Err(e).with_context(|| format!("failed to read config file {:?}", path))
+                }
+            },
+            None => ConfigFile::default(),
+        };
+
+        Config::resolve(cli, &file, path, |name| std::env::var(name).ok())
+    }
+
+    /// Combine the command line and the environment variables looked up by `env` with the
+    /// contents of the config file at `path`
+    pub fn resolve(
+        cli: &Cli,
+        file: &ConfigFile,
+        path: Option<PathBuf>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Config> {
+        let profile = match &cli.profile {
+            Some(name) => {
+                let profile = file.profiles.get(name).ok_or_else(|| match &path {
+                    Some(path) => anyhow!("no profile '{}' in config file {:?}", name, path),
+                    None => anyhow!("no profile '{}' in config file", name),
+                })?;
+                Some((profile, Source::Profile(name.clone())))
+            }
+            None => None,
+        };
+        let layers = Layers {
+            profile,
+            file: &file.settings,
+        };
+
+        let store_env = env_var(&env, "FRE_STORE", |v| {
+            Some(StoreFile::Path(PathBuf::from(v)))
+        })?;
+        // Paths in the config file may start with `~` or name a file in the store directory
+        let store = layers.get(cli.store_file(), store_env, |s| {
+            let path = s.store.as_ref()?.to_string_lossy();
+            Some(StoreFile::Name(PathBuf::from(expand_home(&path))))
+        });
+        let store = match store {
+            Some(setting) => {
+                Setting::new(args::get_store_path(Some(setting.value))?, setting.source)
+            }
+            None => Setting::new(args::get_store_path(None)?, Source::Default),
+        };
+
+        let half_life_env = env_var(&env, "FRE_HALFLIFE", |v| args::parse_half_life(v).ok())?;
+        let sort_method_env = env_var(&env, "FRE_SORT_METHOD", |v| {
+            SortMethod::from_str(v, false).ok()
+        })?;
+        let disabled = env_var(&env, "FRE_DISABLE", parse_bool)?;
+
+        let ignore = layers
+            .get(None, None, |s| s.ignore.clone())
+            .unwrap_or_else(|| Setting::new(Vec::new(), Source::Default));
+
+        Ok(Config {
+            path,
+            profile: cli.profile.clone(),
+            store,
+            namespace: layers
+                .get(cli.namespace.clone(), None, |s| s.namespace.clone())
+                .unwrap_or_else(|| Setting::new(DEFAULT_NAMESPACE.to_string(), Source::Default)),
+            half_life: optional(layers.get(cli.janitor.halflife, half_life_env, |s| s.half_life)),
+            sort_method: layers
+                .get(cli.sort_method, sort_method_env, |s| s.sort_method)
+                .unwrap_or_else(|| Setting::new(SortMethod::Frecent, Source::Default)),
+            stat_digits: optional(layers.get(None, None, |s| s.stat_digits)),
+            limit: optional(layers.get(None, None, |s| s.limit)),
+            ignore: Setting::new(
+                ignore.value.iter().map(|p| expand_home(p)).collect(),
+                ignore.source,
+            ),
+            canonicalize: layers
+                .get(None, None, |s| s.canonicalize)
+                .unwrap_or_else(|| Setting::new(false, Source::Default)),
+            output: layers
+                .get(None, None, |s| s.output)
+                .unwrap_or_else(|| Setting::new(OutputFormat::Text, Source::Default)),
+            disabled: disabled.unwrap_or_else(|| Setting::new(false, Source::Default)),
+        })
+    }
+
+    /// Fill in the limit, number of digits and output format for options that were not
+    /// given on the command line
+    pub fn fill_list_options(&self, options: &mut ListOptions) {
+        if !matches!(options.style, ListStyle::Tree { .. }) {
+            options.limit = options.limit.or(self.limit.value);
+        }
+        options.stat_digits = options.stat_digits.or(self.stat_digits.value);
+        if options.format.is_none() && !options.all_scores {
+            options.output = options.output.or(Some(self.output.value));
+        }
+    }
+
+    /// Whether `item` should not be recorded, because it matches one of the ignore patterns
+    /// or recording is disabled
+    pub fn is_ignored(&self, item: &str) -> bool {
+        self.disabled.value || self.ignore.value.iter().any(|p| glob_match(p, item))
+    }
+
+    /// Return `item` resolved to its canonical path if canonicalization is enabled and
+    /// it is an existing path, or `item` unchanged otherwise. Items of a `kind` other than
+    /// a directory or a file are never resolved
+    pub fn normalize(&self, item: &str, kind: Option<Kind>) -> String {
+        if !self.canonicalize.value || kind.is_some_and(|k| !k.is_path()) {
+            return item.to_string();
+        }
+        match fs::canonicalize(item) {
+            Ok(path) => path.to_string_lossy().into_owned(),
+            Err(_) => item.to_string(),
+        }
+    }
+
+    /// The half life to set on a namespace, if any. A half life given on the command line
+    /// or in the environment changes every namespace, while one from the config file is
+    /// only the default for new namespaces.
+    pub fn half_life_for(&self, new_namespace: bool) -> Option<f64> {
+        match self.half_life.source {
+            Source::CommandLine | Source::Environment(_) => self.half_life.value,
+            _ if new_namespace => self.half_life.value,
+            _ => None,
+        }
+    }
+
+    /// Print each effective setting and where it came from, one per line.
+    /// `store_half_life` is the half life of the namespace after `half_life_for` is applied.
+    pub fn write<W: Write>(&self, w: &mut W, store_half_life: f64) -> Result<()> {
+        match &self.path {
+            Some(path) if path.exists() => writeln!(w, "config_file\t{}", path.display())?,
+            Some(path) => writeln!(w, "config_file\t{}\tnot found", path.display())?,
+            None => writeln!(w, "config_file\tnone")?,
+        }
+        if let Some(profile) = &self.profile {
+            writeln!(w, "profile\t{}", profile)?;
+        }
+
+        let store = self.store.value.display().to_string();
+        write_setting(w, "store", &store, &self.store.source)?;
+        write_setting(
+            w,
+            "namespace",
+            &self.namespace.value,
+            &self.namespace.source,
+        )?;
+        match self.half_life.value.filter(|h| *h == store_half_life) {
+            Some(h) => write_setting(w, "half_life", &h, &self.half_life.source)?,
+            None => write_setting(w, "half_life", &store_half_life, &Source::StoreFile)?,
+        }
+        write_setting(
+            w,
+            "sort_method",
+            &value_name(self.sort_method.value),
+            &self.sort_method.source,
+        )?;
+        write_setting(
+            w,
+            "stat_digits",
+            &display_option(self.stat_digits.value),
+            &self.stat_digits.source,
+        )?;
+        write_setting(
+            w,
+            "limit",
+            &display_option(self.limit.value),
+            &self.limit.source,
+        )?;
+        write_setting(
+            w,
+            "ignore",
+            &self.ignore.value.join(", "),
+            &self.ignore.source,
+        )?;
+        write_setting(
+            w,
+            "canonicalize",
+            &self.canonicalize.value,
+            &self.canonicalize.source,
+        )?;
+        write_setting(
+            w,
+            "output",
+            &value_name(self.output.value),
+            &self.output.source,
+        )?;
+        write_setting(w, "disabled", &self.disabled.value, &self.disabled.source)?;
+
+        Ok(())
+    }
+}
+
+fn write_setting<W: Write, T: fmt::Display>(
+    w: &mut W,
+    name: &str,
+    value: &T,
+    source: &Source,
+) -> io::Result<()> {
+    writeln!(w, "{}\t{}\t{}", name, value, source)
+}
+
+/// Turn a setting without a default into one that is `None` when no layer sets it
+fn optional<T>(setting: Option<Setting<T>>) -> Setting<Option<T>> {
+    match setting {
+        Some(Setting { value, source }) => Setting::new(Some(value), source),
+        None => Setting::new(None, Source::Default),
+    }
+}
+
+fn display_option<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "none".to_string(), |v| v.to_string())
+}
+
+/// The name of a value as it is written on the command line and in the config file
+fn value_name<T: ValueEnum>(value: T) -> String {
+    value
+        .to_possible_value()
+        .map_or_else(String::new, |v| v.get_name().to_string())
+}
+
+/// Replace a leading `~` with the home directory
+fn expand_home(path: &str) -> String {
+    let home = BaseDirs::new().map(|dirs| dirs.home_dir().to_string_lossy().into_owned());
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => home + rest,
+        _ => path.to_string(),
+    }
+}
+
+/// Whether `text` matches `pattern` in full, where `*` matches any run of characters,
+/// including `/`, and `?` matches any single character
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` and of the text it was matched at, to backtrack to
+    let mut star = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    const CONFIG: &str = r#"
+        half_life = 86400
+        sort_method = "frequent"
+        limit = 20
+        ignore = ["/tmp/*", "*/.git"]
+
+        [profiles.work]
+        store = "/work/fre.json"
+        sort_method = "recent"
+        output = "json"
+    "#;
+
+    fn resolve(args: &[&str]) -> Config {
+        let cli = Cli::try_parse_from(args).unwrap();
+        let file = ConfigFile::parse(CONFIG).unwrap();
+        Config::resolve(&cli, &file, None, |_| None).unwrap()
+    }
+
+    #[test]
+    fn parse_profiles() {
+        let file = ConfigFile::parse(CONFIG).unwrap();
+
+        assert_eq!(file.settings.half_life, Some(86400.0));
+        assert_eq!(file.settings.limit, Some(20));
+        assert_eq!(file.profiles["work"].output, Some(OutputFormat::Json));
+        assert_eq!(
+            file.profiles["work"].store,
+            Some(PathBuf::from("/work/fre.json"))
+        );
+    }
+
+    #[test]
+    fn parse_unknown_setting() {
+        assert!(ConfigFile::parse("halflife = 3").is_err());
+        assert!(ConfigFile::parse("[profiles.a]\nsort = \"recent\"").is_err());
+        assert!(ConfigFile::parse("sort_method = \"newest\"").is_err());
+    }
+
+    #[test]
+    fn parse_invalid_half_life() {
+        for (contents, message) in [
+            ("half_life = 0", "invalid value '0' for half_life"),
+            ("half_life = -5", "invalid value '-5' for half_life"),
+            ("half_life = inf", "invalid value 'inf' for half_life"),
+            (
+                "[profiles.work]\nhalf_life = nan",
+                "invalid value 'NaN' for half_life in profile work",
+            ),
+        ] {
+            let error = ConfigFile::parse(contents).unwrap_err();
+            assert_eq!(error.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn layers() {
+        let config = resolve(&["fre", "--profile", "work", "--sorted"]);
+        assert_eq!(
+            config.sort_method,
+            Setting::new(SortMethod::Recent, Source::Profile("work".to_string()))
+        );
+        assert_eq!(
+            config.store,
+            Setting::new(
+                PathBuf::from("/work/fre.json"),
+                Source::Profile("work".to_string())
+            )
+        );
+        assert_eq!(
+            config.half_life,
+            Setting::new(Some(86400.0), Source::ConfigFile)
+        );
+        assert_eq!(config.stat_digits, Setting::new(None, Source::Default));
+
+        let config = resolve(&["fre", "--sort_method", "frecent", "--store", "/s"]);
+        assert_eq!(
+            config.sort_method,
+            Setting::new(SortMethod::Frecent, Source::CommandLine)
+        );
+        assert_eq!(
+            config.store,
+            Setting::new(PathBuf::from("/s"), Source::CommandLine)
+        );
+        assert_eq!(
+            config.output,
+            Setting::new(OutputFormat::Text, Source::Default)
+        );
+    }
+
+    #[test]
+    fn half_life_for_namespaces() {
+        let file = ConfigFile::parse("half_life = 86400\n").unwrap();
+        let env = |name: &str| (name == "FRE_HALFLIFE").then(|| "2".to_string());
+
+        let cli = Cli::try_parse_from(["fre"]).unwrap();
+        let config = Config::resolve(&cli, &file, None, |_| None).unwrap();
+        assert_eq!(config.half_life_for(true), Some(86400.0));
+        assert_eq!(config.half_life_for(false), None);
+
+        let config = Config::resolve(&cli, &file, None, env).unwrap();
+        assert_eq!(config.half_life_for(false), Some(2.0));
+
+        let cli = Cli::try_parse_from(["fre", "--halflife", "1"]).unwrap();
+        let config = Config::resolve(&cli, &file, None, env).unwrap();
+        assert_eq!(config.half_life_for(false), Some(1.0));
+    }
+
+    #[test]
+    fn precedence() {
+        // The setting, its flag, its environment variable, and a value for each of the
+        // command line, a profile, the environment and the top level of the config file
+        let settings = [
+            (
+                "store",
+                "--store",
+                "FRE_STORE",
+                ["/flag", "/profile", "/env", "/file"],
+            ),
+            (
+                "half_life",
+                "--halflife",
+                "FRE_HALFLIFE",
+                ["1", "2", "3", "4"],
+            ),
+            (
+                "sort_method",
+                "--sort_method",
+                "FRE_SORT_METHOD",
+                ["recent", "frecent", "frequent", "recent"],
+            ),
+        ];
+
+        for (name, flag, var, values) in settings {
+            // Every combination of the four layers setting a value or not
+            for set in 0..16 {
+                let layer = |i: usize| set & (1 << i) != 0;
+
+                let mut args = vec!["fre"];
+                if layer(0) {
+                    args.extend([flag, values[0]]);
+                }
+                // Half lives are numbers in the config file, and the rest strings
+                let toml = |value: &str| match name {
+                    "half_life" => value.to_string(),
+                    _ => format!("{:?}", value),
+                };
+                let mut contents = String::new();
+                if layer(3) {
+                    contents += &format!("{} = {}\n", name, toml(values[3]));
+                }
+                contents += "[profiles.p]\n";
+                if layer(1) {
+                    contents += &format!("{} = {}\n", name, toml(values[1]));
+                }
+                args.extend(["--profile", "p"]);
+
+                let cli = Cli::try_parse_from(&args).unwrap();
+                let file = ConfigFile::parse(&contents).unwrap();
+                let env = |v: &str| (v == var && layer(2)).then(|| values[2].to_string());
+                let config = Config::resolve(&cli, &file, None, env).unwrap();
+
+                let source = match config_source(&config, name) {
+                    Source::CommandLine => 0,
+                    Source::Profile(_) => 1,
+                    Source::Environment(_) => 2,
+                    Source::ConfigFile => 3,
+                    _ => 4,
+                };
+                let expected = (0..4).find(|&i| layer(i)).unwrap_or(4);
+                assert_eq!(source, expected, "{} with layers {:04b}", name, set);
+                if name == "store" && expected < 4 {
+                    assert_eq!(config.store.value, PathBuf::from(values[expected]));
+                }
+            }
+        }
+    }
+
+    fn config_source<'a>(config: &'a Config, name: &str) -> &'a Source {
+        match name {
+            "store" => &config.store.source,
+            "half_life" => &config.half_life.source,
+            _ => &config.sort_method.source,
+        }
+    }
+
+    #[test]
+    fn environment() {
+        let cli = Cli::try_parse_from(["fre"]).unwrap();
+        let file = ConfigFile::default();
+        let env = |name: &str| match name {
+            "FRE_STORE" => Some("/env/fre.json".to_string()),
+            "FRE_HALFLIFE" => Some(String::new()),
+            "FRE_DISABLE" => Some("1".to_string()),
+            _ => None,
+        };
+        let config = Config::resolve(&cli, &file, None, env).unwrap();
+
+        assert_eq!(config.store.value, PathBuf::from("/env/fre.json"));
+        // Empty variables are ignored
+        assert_eq!(config.half_life, Setting::new(None, Source::Default));
+        assert!(config.is_ignored("/home"));
+
+        let env = |name: &str| (name == "FRE_HALFLIFE").then(|| "week".to_string());
+        let error = Config::resolve(&cli, &file, None, env).unwrap_err();
+        assert_eq!(error.to_string(), "invalid value 'week' for FRE_HALFLIFE");
+        for value in ["0", "-5", "inf", "NaN"] {
+            let env = |name: &str| (name == "FRE_HALFLIFE").then(|| value.to_string());
+            let error = Config::resolve(&cli, &file, None, env).unwrap_err();
+            let message = format!("invalid value '{}' for FRE_HALFLIFE", value);
+            assert_eq!(error.to_string(), message);
+        }
+        for (value, disabled) in [("yes", true), ("true", true), ("0", false), ("no", false)] {
+            let env = |name: &str| (name == "FRE_DISABLE").then(|| value.to_string());
+            let config = Config::resolve(&cli, &file, None, env).unwrap();
+            assert_eq!(config.disabled.value, disabled, "FRE_DISABLE={}", value);
+        }
+
+        let env = |name: &str| (name == "FRE_DISABLE").then(|| "off".to_string());
+        let error = Config::resolve(&cli, &file, None, env).unwrap_err();
+        assert_eq!(error.to_string(), "invalid value 'off' for FRE_DISABLE");
+    }
+
+    #[test]
+    fn missing_profile() {
+        let cli = Cli::try_parse_from(["fre", "--profile", "home"]).unwrap();
+        let file = ConfigFile::parse(CONFIG).unwrap();
+        assert!(Config::resolve(&cli, &file, None, |_| None).is_err());
+    }
+
+    #[test]
+    fn ignore_patterns() {
+        let config = resolve(&["fre"]);
+
+        assert!(config.is_ignored("/tmp/a/b"));
+        assert!(config.is_ignored("/home/user/src/.git"));
+        assert!(!config.is_ignored("/tmp"));
+        assert!(!config.is_ignored("/home/user/.github"));
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("/a/*/c", "/a/b/b/c"));
+        assert!(glob_match("/a?c", "/abc"));
+        assert!(glob_match("**x", "yyx"));
+        assert!(!glob_match("/a?c", "/ac"));
+        assert!(!glob_match("/a*b", "/acbc"));
+    }
+}
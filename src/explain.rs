@@ -0,0 +1,126 @@
+use crate::args::SortMethod;
+use crate::output::template::human_duration;
+use crate::store::FrecencyStore;
+use crate::timestamp::format_rfc3339;
+use anyhow::{anyhow, Result};
+use std::io::Write;
+
+/// Scores that `--explain` says when the frecency will decay below
+const THRESHOLDS: [f64; 2] = [1.0, 0.1];
+
+const SECS_PER_DAY: f64 = 60.0 * 60.0 * 24.0;
+
+/// Print the stored statistics of `item`, how its frecency is calculated from them,
+/// its rank under each sort method and when its frecency will decay below each of
+/// `THRESHOLDS`, as one `name\tvalue` line each
+pub fn write_explanation<W: Write>(
+    w: &mut W,
+    store: &FrecencyStore,
+    item: &str,
+    current_time: f64,
+) -> Result<()> {
+    let stats = store
+        .item(item)
+        .ok_or_else(|| anyhow!("not in the store: {}", item))?;
+
+    let half_lives = (current_time - stats.reference_time()) / stats.half_life();
+    writeln!(w, "item\t{}", stats.item)?;
+    writeln!(w, "stored_frecency\t{:.3}", stats.stored_frecency())?;
+    writeln!(
+        w,
+        "reference_time\t{:.0}\t{}",
+        stats.reference_time(),
+        format_rfc3339(stats.reference_time())
+    )?;
+    writeln!(
+        w,
+        "half_life\t{}\t{}",
+        stats.half_life(),
+        human_duration(stats.half_life())
+    )?;
+    writeln!(
+        w,
+        "frecency\t{:.3}\tstored_frecency / 2^{:.3}, for the half lives since reference_time",
+        stats.get_frecency(current_time),
+        half_lives
+    )?;
+    writeln!(
+        w,
+        "last_access\t{:.0}\t{}\t{} ago",
+        stats.last_access(),
+        format_rfc3339(stats.last_access()),
+        human_duration(current_time - stats.last_access())
+    )?;
+    writeln!(w, "num_accesses\t{}", stats.num_accesses)?;
+    writeln!(w, "pinned\t{}", stats.pinned)?;
+
+    for (name, method) in [
+        ("frecent", SortMethod::Frecent),
+        ("frequent", SortMethod::Frequent),
+        ("recent", SortMethod::Recent),
+    ] {
+        let sorted = store.sorted(method);
+        let rank = sorted
+            .iter()
+            .position(|i| i.item == stats.item)
+            .unwrap_or(0)
+            + 1;
+        writeln!(w, "rank_{}\t{} of {}", name, rank, sorted.len())?;
+    }
+
+    for score in THRESHOLDS {
+        match stats.secs_until_below(score, current_time) {
+            Some(secs) => writeln!(w, "below_{}\tin {:.1} days", score, secs / SECS_PER_DAY)?,
+            None => writeln!(w, "below_{}\talready", score)?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explain(store: &FrecencyStore, item: &str, current_time: f64) -> Vec<String> {
+        let mut b = Vec::new();
+        write_explanation(&mut b, store, item, current_time).unwrap();
+        String::from_utf8(b)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn ranks_and_decay() {
+        let mut store = FrecencyStore::default();
+        let t = crate::current_time_secs();
+        store.set_half_life(SECS_PER_DAY);
+        store.add_at("/often", t - SECS_PER_DAY).unwrap();
+        store.add_at("/often", t - SECS_PER_DAY).unwrap();
+        store.add_at("/often", t - SECS_PER_DAY).unwrap();
+        store.add_at("/once", t).unwrap();
+
+        let lines = explain(&store, "/often", t + SECS_PER_DAY);
+
+        assert_eq!(lines[0], "item\t/often");
+        assert!(lines[4].starts_with("frecency\t0.750\tstored_frecency / 2^1.000"));
+        assert_eq!(lines[6], "num_accesses\t3");
+        assert_eq!(lines[8], "rank_frecent\t1 of 2");
+        assert_eq!(lines[9], "rank_frequent\t1 of 2");
+        assert_eq!(lines[10], "rank_recent\t2 of 2");
+        assert_eq!(lines[11], "below_1\talready");
+        assert_eq!(lines[12], "below_0.1\tin 2.9 days");
+    }
+
+    #[test]
+    fn missing_item() {
+        let store = FrecencyStore::default();
+        let mut b = Vec::new();
+
+        let err = write_explanation(&mut b, &store, "/missing", 0.0).unwrap_err();
+
+        assert_eq!(err.to_string(), "not in the store: /missing");
+    }
+}
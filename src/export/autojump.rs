@@ -0,0 +1,42 @@
+use super::RankedItem;
+use anyhow::Result;
+use std::io::Write;
+
+/// The weight autojump adds for each visit to a directory
+const VISIT_WEIGHT: f64 = 10.0;
+
+/// Write an autojump data file, with lines of the form `weight<TAB>path`.
+/// autojump combines visits as `sqrt(weight^2 + 10^2)`, so a rank of `n` visits
+/// is written as a weight of `10 * sqrt(n)`.
+pub fn write<W: Write>(w: &mut W, items: &[RankedItem]) -> Result<()> {
+    for item in items {
+        writeln!(w, "{}\t{}", VISIT_WEIGHT * item.rank.sqrt(), item.item)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_lines() {
+        let items = [
+            RankedItem {
+                item: "/home/user",
+                rank: 4.0,
+                last_access: 1703721600.0,
+            },
+            RankedItem {
+                item: "/",
+                rank: 1.0,
+                last_access: 1703721000.0,
+            },
+        ];
+
+        let mut b = Vec::new();
+        write(&mut b, &items).unwrap();
+
+        assert_eq!(String::from_utf8(b).unwrap(), "20\t/home/user\n10\t/\n");
+    }
+}
@@ -0,0 +1,61 @@
+use crate::stats::ItemStats;
+use anyhow::Result;
+use std::io::Write;
+
+/// The header row naming each column
+const HEADER: &str = "item,frecency,num_accesses,last_access";
+
+/// Write every item as comma-separated values, with the frecency decayed to
+/// `current_time` and the last access in seconds since the epoch
+pub fn write<W: Write>(w: &mut W, items: &[ItemStats], current_time: f64) -> Result<()> {
+    writeln!(w, "{}", HEADER)?;
+    for item in items {
+        writeln!(
+            w,
+            "{},{},{},{}",
+            quote(&item.item),
+            item.get_frecency(current_time),
+            item.num_accesses,
+            item.last_access()
+        )?;
+    }
+    Ok(())
+}
+
+/// Quote a field if it contains a comma, quote or line break, doubling any quotes
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_fields() {
+        assert_eq!(quote("/home/user"), "/home/user");
+        assert_eq!(quote("/a,b"), "\"/a,b\"");
+        assert_eq!(quote("/\"quoted\"\n"), "\"/\"\"quoted\"\"\n\"");
+    }
+
+    #[test]
+    fn write_rows() {
+        let t = 1703721600.0;
+        let mut item = ItemStats::new("/a,b".to_string(), t, 1e12);
+        item.update_frecency_at(2.5, t).unwrap();
+        item.update_num_accesses(3);
+        item.update_last_access(t - 0.5);
+
+        let mut b = Vec::new();
+        write(&mut b, &[item], t).unwrap();
+
+        assert_eq!(
+            String::from_utf8(b).unwrap(),
+            "item,frecency,num_accesses,last_access\n\"/a,b\",2.5,3,1703721599.5\n"
+        );
+    }
+}
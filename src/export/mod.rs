@@ -0,0 +1,88 @@
+mod autojump;
+mod csv;
+mod z;
+mod zoxide;
+
+use super::stats::ItemStats;
+use anyhow::Result;
+use clap::ValueEnum;
+use std::io::Write;
+
+/// The formats that can be written with `--export`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportKind {
+    /// z's `~/.z`, also read by zsh-z and fasd
+    Z,
+    /// autojump's `autojump.txt`
+    Autojump,
+    /// zoxide's `db.zo`
+    Zoxide,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+/// Write `items` in the format of `kind`, with scores decayed to `current_time`
+pub fn write_export<W: Write>(
+    w: &mut W,
+    items: &[ItemStats],
+    kind: ExportKind,
+    current_time: f64,
+) -> Result<()> {
+    match kind {
+        ExportKind::Z => z::write(w, &ranked(items, current_time, &['|'])),
+        ExportKind::Autojump => autojump::write(w, &ranked(items, current_time, &[])),
+        ExportKind::Zoxide => zoxide::write(w, &ranked(items, current_time, &[])),
+        ExportKind::Csv => csv::write(w, items, current_time),
+    }
+}
+
+/// An item with a positive score, as exported to tools that rank items by visits
+struct RankedItem<'a> {
+    item: &'a str,
+    /// The decayed frecency, which counts visits weighted by how recent they were
+    rank: f64,
+    /// Time of the last access in seconds since the epoch
+    last_access: f64,
+}
+
+/// Map each item onto a rank, skipping items the line-based formats cannot represent,
+/// items containing one of the format's `reserved` separators and items without a
+/// positive score, which other tools would discard
+fn ranked<'a>(items: &'a [ItemStats], current_time: f64, reserved: &[char]) -> Vec<RankedItem<'a>> {
+    items
+        .iter()
+        .map(|i| RankedItem {
+            item: &i.item,
+            rank: i.get_frecency(current_time),
+            last_access: i.last_access(),
+        })
+        .filter(|i| i.rank > 0.0 && !i.item.contains(['\n', '\r']) && !i.item.contains(reserved))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn z_skips_pipes() {
+        let t = 1703721600.0;
+        let items: Vec<ItemStats> = ["/home/user", "/with|pipe"]
+            .iter()
+            .map(|item| {
+                let mut stats = ItemStats::new(item.to_string(), t, 1e12);
+                stats.update_frecency_at(1.0, t).unwrap();
+                stats.update_last_access(t);
+                stats
+            })
+            .collect();
+
+        let mut b = Vec::new();
+        write_export(&mut b, &items, ExportKind::Z, t).unwrap();
+        assert_eq!(String::from_utf8(b).unwrap(), "/home/user|1|1703721600\n");
+
+        let mut b = Vec::new();
+        write_export(&mut b, &items, ExportKind::Autojump, t).unwrap();
+        assert!(String::from_utf8(b).unwrap().contains("/with|pipe"));
+    }
+}
@@ -0,0 +1,40 @@
+use super::RankedItem;
+use anyhow::Result;
+use std::io::Write;
+
+/// Write a z data file, with lines of the form `path|rank|time`
+pub fn write<W: Write>(w: &mut W, items: &[RankedItem]) -> Result<()> {
+    for item in items {
+        writeln!(w, "{}|{}|{}", item.item, item.rank, item.last_access as u64)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_lines() {
+        let items = [
+            RankedItem {
+                item: "/home/user",
+                rank: 12.5,
+                last_access: 1703721600.7,
+            },
+            RankedItem {
+                item: "/tmp",
+                rank: 1.0,
+                last_access: 1703721000.0,
+            },
+        ];
+
+        let mut b = Vec::new();
+        write(&mut b, &items).unwrap();
+
+        assert_eq!(
+            String::from_utf8(b).unwrap(),
+            "/home/user|12.5|1703721600\n/tmp|1|1703721000\n"
+        );
+    }
+}
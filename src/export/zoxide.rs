@@ -0,0 +1,56 @@
+use super::RankedItem;
+use anyhow::Result;
+use std::io::Write;
+
+/// The version of zoxide's database format that is written
+const VERSION: u32 = 3;
+
+/// Write a zoxide database. The database is encoded with bincode as a `u32`
+/// version followed by a list of `(path: String, rank: f64, last_accessed: u64)`,
+/// with integers in little endian and lengths as `u64`.
+pub fn write<W: Write>(w: &mut W, items: &[RankedItem]) -> Result<()> {
+    w.write_all(&VERSION.to_le_bytes())?;
+    w.write_all(&(items.len() as u64).to_le_bytes())?;
+    for item in items {
+        w.write_all(&(item.item.len() as u64).to_le_bytes())?;
+        w.write_all(item.item.as_bytes())?;
+        w.write_all(&item.rank.to_le_bytes())?;
+        w.write_all(&(item.last_access as u64).to_le_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{self, ImportKind, ImportedItem};
+
+    #[test]
+    fn round_trip() {
+        let items = [RankedItem {
+            item: "/home/user",
+            rank: 4.5,
+            last_access: 1703721600.0,
+        }];
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write(&mut file.as_file(), &items).unwrap();
+        let imported = import::read_items(
+            ImportKind::Zoxide,
+            file.path(),
+            import::HistoryTarget::Directories,
+            1.0,
+        )
+        .unwrap();
+
+        assert_eq!(
+            imported,
+            vec![ImportedItem {
+                item: "/home/user".to_string(),
+                weight: 4.5,
+                num_accesses: 5,
+                last_access: 1703721600.0,
+            }]
+        );
+    }
+}
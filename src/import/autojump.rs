@@ -0,0 +1,73 @@
+use super::ImportedItem;
+use anyhow::{anyhow, Context, Result};
+
+/// The weight autojump adds for each visit to a directory
+const VISIT_WEIGHT: f64 = 10.0;
+
+/// Parse an autojump data file, with lines of the form `weight<TAB>path`.
+/// autojump combines visits as `sqrt(weight^2 + 10^2)`, so the number of visits
+/// is recovered as `(weight / 10)^2`. autojump does not store access times, so
+/// every item is treated as last accessed at `last_access`.
+pub fn parse(data: &str, last_access: f64) -> Result<Vec<ImportedItem>> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            parse_line(line, last_access).with_context(|| format!("line {}", idx + 1))
+        })
+        .collect()
+}
+
+fn parse_line(line: &str, last_access: f64) -> Result<ImportedItem> {
+    let (weight, path) = line
+        .split_once('\t')
+        .ok_or_else(|| anyhow!("expected a weight and a path separated by a tab"))?;
+    let weight: f64 = weight
+        .parse()
+        .map_err(|_| anyhow!("invalid weight '{}'", weight))?;
+
+    let visits = (weight / VISIT_WEIGHT).powi(2);
+    ImportedItem::new(
+        path.to_string(),
+        visits,
+        visits.round().max(1.0) as i32,
+        last_access,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lines() {
+        let data = "30.0\t/home/user\n10\t/tmp/with\ttab\n";
+
+        let items = parse(data, 1703721600.0).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                ImportedItem {
+                    item: "/home/user".to_string(),
+                    weight: 9.0,
+                    num_accesses: 9,
+                    last_access: 1703721600.0,
+                },
+                ImportedItem {
+                    item: "/tmp/with\ttab".to_string(),
+                    weight: 1.0,
+                    num_accesses: 1,
+                    last_access: 1703721600.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("/home/user\n", 0.0).is_err());
+        assert!(parse("lots\t/home/user\n", 0.0).is_err());
+        assert!(parse("1e300\t/home/user\n", 0.0).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use super::ImportedItem;
+use crate::timestamp::parse_timestamp;
+use anyhow::{anyhow, bail, Result};
+
+/// The columns every row must have, in the order `--export csv` writes them
+const COLUMNS: [&str; 4] = ["item", "frecency", "num_accesses", "last_access"];
+
+/// Parse comma-separated values with a header row naming the `item`, `frecency`,
+/// `num_accesses` and `last_access` columns in any order, as written by `--export csv`.
+/// Each frecency is taken as decayed to `current_time`, so the item is given the weight
+/// at its last access that decays to that frecency with `half_life`.
+pub fn parse(data: &str, half_life: f64, current_time: f64) -> Result<Vec<ImportedItem>> {
+    let mut records = records(data)?.into_iter();
+    let (_, header) = records
+        .next()
+        .ok_or_else(|| anyhow!("missing header row"))?;
+
+    let mut indices = [0; 4];
+    for (index, column) in indices.iter_mut().zip(COLUMNS) {
+        *index = header
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(column))
+            .ok_or_else(|| anyhow!("missing column '{}'", column))?;
+    }
+
+    records
+        .map(|(line, fields)| {
+            let field = |i: usize| -> Result<&str> {
+                fields
+                    .get(indices[i])
+                    .map(|f| f.as_str())
+                    .ok_or_else(|| anyhow!("line {}: missing {}", line, COLUMNS[i]))
+            };
+            let invalid = |i: usize, value: &str| {
+                anyhow!("line {}: invalid {} '{}'", line, COLUMNS[i], value)
+            };
+
+            let item = field(0)?.to_string();
+            let (frecency, num_accesses, last_access) = (field(1)?, field(2)?, field(3)?);
+            let frecency = frecency
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .ok_or_else(|| invalid(1, frecency))?;
+            let num_accesses: i32 = num_accesses
+                .trim()
+                .parse()
+                .ok()
+                .filter(|n| *n >= 0)
+                .ok_or_else(|| invalid(2, num_accesses))?;
+            let last_access =
+                parse_timestamp(last_access.trim()).map_err(|_| invalid(3, last_access))?;
+
+            // The weight grows without bound the longer ago the last access was,
+            // so rows where it overflows are rejected
+            let weight = if frecency == 0.0 {
+                0.0
+            } else {
+                frecency * 2.0f64.powf((current_time - last_access) / half_life)
+            };
+            ImportedItem::new(item, weight, num_accesses, last_access)
+                .map_err(|e| anyhow!("line {}: {}", line, e))
+        })
+        .collect()
+}
+
+/// Split comma-separated values into records of fields, each with the line it starts on.
+/// Quoted fields may contain commas, line breaks and quotes written as `""`.
+/// Empty lines are skipped.
+fn records(data: &str) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = data.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            field.push(c);
+                        }
+                        None => bail!("line {}: unterminated quoted field", start),
+                    }
+                }
+            }
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+                records.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            c => field.push(c),
+        }
+    }
+    if quoted || !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push((start, fields));
+    }
+
+    records.retain(|(_, fields)| fields.len() > 1 || !fields[0].is_empty());
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{write_export, ExportKind};
+    use crate::import::import;
+    use crate::store::FrecencyStore;
+
+    #[test]
+    fn quoted_fields() {
+        let data = "a,b\r\n\"x,\"\"y\"\"\nz\",\"\"\n\nlast,row";
+
+        assert_eq!(
+            records(data).unwrap(),
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["x,\"y\"\nz".to_string(), "".to_string()]),
+                (5, vec!["last".to_string(), "row".to_string()]),
+            ]
+        );
+        assert!(records("a,\"b\n").is_err());
+    }
+
+    #[test]
+    fn parse_rows() {
+        let data = "last_access,item,num_accesses,frecency\n\
+                    2023-12-28T00:00:00Z,/home,3,2.5\n";
+
+        let items = parse(data, 100.0, 1703721700.0).unwrap();
+
+        assert_eq!(
+            items,
+            vec![ImportedItem {
+                item: "/home".to_string(),
+                weight: 5.0,
+                num_accesses: 3,
+                last_access: 1703721600.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("item,frecency,num_accesses\n", 100.0, 0.0).unwrap_err();
+        assert_eq!(err.to_string(), "missing column 'last_access'");
+
+        let data = "item,frecency,num_accesses,last_access\n/a,1,1,0\n/b,x,1,0\n";
+        let err = parse(data, 100.0, 0.0).unwrap_err();
+        assert_eq!(err.to_string(), "line 3: invalid frecency 'x'");
+
+        let header = "item,frecency,num_accesses,last_access\n";
+        let t = 1703721600.0;
+        for (row, message) in [
+            ("/a,inf,1,0", "line 2: invalid frecency 'inf'"),
+            ("/a,NaN,1,0", "line 2: invalid frecency 'NaN'"),
+            ("/a,1,-1,0", "line 2: invalid num_accesses '-1'"),
+            (
+                "/a,1,1,4102444800",
+                "line 2: invalid last_access '4102444800'",
+            ),
+            ("/a,1,1,0", "line 2: invalid weight inf"),
+        ] {
+            let err = parse(&format!("{}{}\n", header, row), 100.0, t).unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+
+        // A frecency of zero stays zero however long ago the last access was
+        let items = parse(&format!("{}/a,0,1,0\n", header), 100.0, t).unwrap();
+        assert_eq!(items[0].weight, 0.0);
+    }
+
+    #[test]
+    fn round_trip() {
+        let t = crate::current_time_secs();
+        let mut store = FrecencyStore::default();
+        store.add_at("/home", t - 1000.0).unwrap();
+        store.add_at("/home", t - 100.0).unwrap();
+        store.adjust("/a,\"b\"\nc", 2.5).unwrap();
+        store.add_at("/", t - 1e6).unwrap();
+        let items = store.sorted(crate::args::SortMethod::Frecent);
+
+        let mut b = Vec::new();
+        write_export(&mut b, &items, ExportKind::Csv, t).unwrap();
+        let mut imported = FrecencyStore::default();
+        import(
+            &mut imported,
+            &parse(&String::from_utf8(b).unwrap(), 259200.0, t).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(imported.items.len(), items.len());
+        for item in &items {
+            let other = imported.items.iter().find(|i| i.item == item.item).unwrap();
+            assert!((other.get_frecency(t) - item.get_frecency(t)).abs() < 1e-9);
+            assert_eq!(other.num_accesses, item.num_accesses);
+            assert_eq!(other.last_access(), item.last_access());
+        }
+    }
+}
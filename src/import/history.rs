@@ -0,0 +1,501 @@
+use super::{check_time, ImportedItem};
+use anyhow::{Context, Result};
+use std::path::{Component, Path, PathBuf};
+
+/// Commands that change the working directory
+const CD_COMMANDS: [&str; 2] = ["cd", "pushd"];
+
+/// Commands that open the files given as arguments
+const EDITOR_COMMANDS: [&str; 11] = [
+    "vi",
+    "vim",
+    "nvim",
+    "emacs",
+    "emacsclient",
+    "nano",
+    "micro",
+    "hx",
+    "kak",
+    "code",
+    "subl",
+];
+
+/// Words that run the rest of the command line as a command
+const PREFIX_COMMANDS: [&str; 6] = ["sudo", "command", "builtin", "noglob", "nocorrect", "time"];
+
+/// Which paths to extract from the commands in a shell history
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryTarget {
+    /// Directories changed to with `cd` or `pushd`
+    Directories,
+    /// Files opened with a text editor
+    Files,
+}
+
+/// A command read from a shell history, with the time it was run in seconds since the epoch
+#[derive(Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub time: f64,
+    pub command: String,
+}
+
+/// Parse a zsh history written with `EXTENDED_HISTORY`, with entries of the form
+/// `: time:duration;command`. Entries without a timestamp are skipped.
+pub fn parse_zsh(data: &[u8]) -> Result<Vec<HistoryEntry>> {
+    let data = String::from_utf8_lossy(&unmetafy(data)).into_owned();
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+    let mut continued = false;
+
+    for (idx, line) in data.lines().enumerate() {
+        // Multi-line commands are written with a backslash before each newline
+        if continued {
+            if let Some(entry) = entries.last_mut() {
+                entry.command.pop();
+                entry.command.push('\n');
+                entry.command.push_str(line);
+            }
+            continued = line.ends_with('\\');
+            continue;
+        }
+
+        let entry = line.strip_prefix(": ").and_then(|rest| {
+            let (header, command) = rest.split_once(';')?;
+            let (time, _duration) = header.split_once(':')?;
+            Some(HistoryEntry {
+                time: parse_time(time)?,
+                command: command.to_string(),
+            })
+        });
+        if let Some(entry) = entry {
+            check_time(entry.time).with_context(|| format!("line {}", idx + 1))?;
+            continued = entry.command.ends_with('\\');
+            entries.push(entry);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Parse a bash history written with `HISTTIMEFORMAT` set, where each command follows
+/// a `#time` comment. Commands before the first timestamp are skipped.
+pub fn parse_bash(data: &str) -> Result<Vec<HistoryEntry>> {
+    let mut time = None;
+    let mut entries = Vec::new();
+
+    for (idx, line) in data.lines().enumerate() {
+        if let Some(t) = line.strip_prefix('#').and_then(parse_time) {
+            time = Some(check_time(t).with_context(|| format!("line {}", idx + 1))?);
+        } else if let Some(time) = time {
+            entries.push(HistoryEntry {
+                time,
+                command: line.to_string(),
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Parse a fish history, with entries of the form `- cmd: command` followed by
+/// an indented `when: time`
+pub fn parse_fish(data: &str) -> Result<Vec<HistoryEntry>> {
+    let mut entries = Vec::new();
+    let mut command: Option<String> = None;
+
+    for (idx, line) in data.lines().enumerate() {
+        if let Some(cmd) = line.strip_prefix("- cmd: ") {
+            command = Some(unescape_fish(cmd));
+        } else if let Some(time) = line.trim_start().strip_prefix("when: ") {
+            if let (Some(command), Some(time)) = (command.take(), parse_time(time)) {
+                let time = check_time(time).with_context(|| format!("line {}", idx + 1))?;
+                entries.push(HistoryEntry { time, command });
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Parse a timestamp in a shell history, which is a whole number of seconds since the epoch
+fn parse_time(time: &str) -> Option<f64> {
+    time.trim().parse::<i64>().ok().map(|t| t as f64)
+}
+
+/// Replay the commands of a shell history in order, tracking the working directory
+/// to resolve relative paths, and return a visit to every path of kind `target`.
+/// Paths relative to an unknown working directory or containing shell expansions
+/// are skipped.
+pub fn replay(entries: &[HistoryEntry], home: &Path, target: HistoryTarget) -> Vec<ImportedItem> {
+    let mut visits = Vec::new();
+    let mut cwd: Option<PathBuf> = None;
+    let mut previous: Option<PathBuf> = None;
+
+    for entry in entries {
+        for words in split_commands(&entry.command) {
+            let mut words = words.as_slice();
+            while let [Word::Literal(w), rest @ ..] = words {
+                if !PREFIX_COMMANDS.contains(&w.as_str()) {
+                    break;
+                }
+                words = rest;
+            }
+            let Some((Word::Literal(name), args)) = words.split_first() else {
+                continue;
+            };
+
+            if CD_COMMANDS.contains(&name.as_str()) {
+                let arg = args.iter().find(|a| match a {
+                    Word::Literal(a) => a == "-" || !a.starts_with('-'),
+                    Word::Expanded => true,
+                });
+                let dir = match arg {
+                    None => Some(home.to_path_buf()),
+                    Some(Word::Literal(a)) if a == "-" => previous.clone(),
+                    Some(Word::Literal(a)) => resolve(a, cwd.as_deref(), home),
+                    Some(Word::Expanded) => None,
+                };
+
+                previous = std::mem::replace(&mut cwd, dir.clone());
+                if let (Some(dir), HistoryTarget::Directories) = (dir, target) {
+                    visits.push(visit(dir, entry.time));
+                }
+            } else if name == "popd" {
+                previous = cwd.take();
+            } else if EDITOR_COMMANDS.contains(&name.as_str()) && target == HistoryTarget::Files {
+                for arg in args {
+                    if let Word::Literal(a) = arg {
+                        if a.starts_with(['-', '+']) {
+                            continue;
+                        }
+                        if let Some(file) = resolve(a, cwd.as_deref(), home) {
+                            visits.push(visit(file, entry.time));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    visits
+}
+
+fn visit(path: PathBuf, time: f64) -> ImportedItem {
+    ImportedItem {
+        item: path.to_string_lossy().into_owned(),
+        weight: 1.0,
+        num_accesses: 1,
+        last_access: time,
+    }
+}
+
+/// Resolve a path given to a command into an absolute path without `.` or `..`
+fn resolve(path: &str, cwd: Option<&Path>, home: &Path) -> Option<PathBuf> {
+    let path = if path == "~" {
+        home.to_path_buf()
+    } else if let Some(rest) = path.strip_prefix("~/") {
+        home.join(rest)
+    } else if path.starts_with('/') {
+        PathBuf::from(path)
+    } else if path.starts_with('~') {
+        // Another user's home directory
+        return None;
+    } else {
+        cwd?.join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            c => resolved.push(c),
+        }
+    }
+    Some(resolved)
+}
+
+/// A word of a command line
+#[derive(Debug, PartialEq)]
+enum Word {
+    /// A word without any expansions, with quotes and escapes removed
+    Literal(String),
+    /// A word whose value depends on a variable, command substitution or glob
+    Expanded,
+}
+
+/// Split a command line into simple commands separated by `;`, `&`, `|` or newlines,
+/// each a list of words
+fn split_commands(line: &str) -> Vec<Vec<Word>> {
+    let mut commands = vec![Vec::new()];
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut expanded = false;
+    let mut chars = line.chars();
+
+    let end_word = |commands: &mut Vec<Vec<Word>>, word: &mut String, expanded: &mut bool| {
+        let word = std::mem::take(word);
+        commands
+            .last_mut()
+            .unwrap()
+            .push(if std::mem::take(expanded) {
+                Word::Expanded
+            } else {
+                Word::Literal(word)
+            });
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                in_word = true;
+                match chars.next() {
+                    Some('\n') | None => {}
+                    Some(c) => word.push(c),
+                }
+            }
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        '$' | '`' => {
+                            expanded = true;
+                            word.push(c)
+                        }
+                        c => word.push(c),
+                    }
+                }
+            }
+            ';' | '&' | '|' | '\n' | ' ' | '\t' => {
+                if in_word {
+                    end_word(&mut commands, &mut word, &mut expanded);
+                    in_word = false;
+                }
+                if !matches!(c, ' ' | '\t') && !commands.last().unwrap().is_empty() {
+                    commands.push(Vec::new());
+                }
+            }
+            '$' | '`' | '*' | '?' | '[' | '{' | '(' => {
+                in_word = true;
+                expanded = true;
+                word.push(c);
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        end_word(&mut commands, &mut word, &mut expanded);
+    }
+
+    commands.retain(|c| !c.is_empty());
+    commands
+}
+
+/// Undo zsh's escaping of special bytes in its history file, where each is written
+/// as 0x83 followed by the byte XOR 0x20
+fn unmetafy(data: &[u8]) -> Vec<u8> {
+    let mut bytes = data.iter();
+    let mut unmetafied = Vec::with_capacity(data.len());
+    while let Some(&b) = bytes.next() {
+        match b {
+            0x83 => unmetafied.extend(bytes.next().map(|b| b ^ 0x20)),
+            b => unmetafied.push(b),
+        }
+    }
+    unmetafied
+}
+
+/// Undo fish's escaping of backslashes and newlines in its history file
+fn unescape_fish(cmd: &str) -> String {
+    let mut unescaped = String::with_capacity(cmd.len());
+    let mut chars = cmd.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                unescaped.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                unescaped.push('\\');
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(commands: &[&str]) -> Vec<HistoryEntry> {
+        commands
+            .iter()
+            .enumerate()
+            .map(|(i, command)| HistoryEntry {
+                time: i as f64,
+                command: command.to_string(),
+            })
+            .collect()
+    }
+
+    fn replayed(commands: &[&str], target: HistoryTarget) -> Vec<(String, f64)> {
+        replay(&entries(commands), Path::new("/home/user"), target)
+            .into_iter()
+            .map(|v| (v.item, v.last_access))
+            .collect()
+    }
+
+    #[test]
+    fn zsh_extended() {
+        let data = b": 1703721600:0;cd /tmp\nplain\n: 1703721601:2;echo a\\\nb\n: 1703721602:0;cd /caf\x83\xe3\x83\x89\n";
+
+        assert_eq!(
+            parse_zsh(data).unwrap(),
+            vec![
+                HistoryEntry {
+                    time: 1703721600.0,
+                    command: "cd /tmp".to_string()
+                },
+                HistoryEntry {
+                    time: 1703721601.0,
+                    command: "echo a\nb".to_string()
+                },
+                HistoryEntry {
+                    time: 1703721602.0,
+                    command: "cd /caf\u{e9}".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bash_timestamps() {
+        let data = "cd /untimed\n#1703721600\ncd /tmp\n#1703721601\nls\n";
+
+        assert_eq!(
+            parse_bash(data).unwrap(),
+            vec![
+                HistoryEntry {
+                    time: 1703721600.0,
+                    command: "cd /tmp".to_string()
+                },
+                HistoryEntry {
+                    time: 1703721601.0,
+                    command: "ls".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fish_entries() {
+        let data = "- cmd: cd /tmp\n  when: 1703721600\n  paths:\n    - /tmp\n- cmd: echo a\\nb \\\\\n  when: 1703721601\n";
+
+        assert_eq!(
+            parse_fish(data).unwrap(),
+            vec![
+                HistoryEntry {
+                    time: 1703721600.0,
+                    command: "cd /tmp".to_string()
+                },
+                HistoryEntry {
+                    time: 1703721601.0,
+                    command: "echo a\nb \\".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_timestamps() {
+        // Timestamps that are not whole numbers are not timestamps at all
+        assert_eq!(parse_zsh(b": inf:0;cd /tmp\n").unwrap(), vec![]);
+        assert_eq!(parse_bash("#1e300\ncd /tmp\n").unwrap(), vec![]);
+        assert_eq!(parse_fish("- cmd: cd /tmp\n  when: NaN\n").unwrap(), vec![]);
+
+        let err = parse_zsh(b": 1:0;ls\n: 4102444800:0;cd /tmp\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "line 2: time 2100-01-01T00:00:00Z is in the future"
+        );
+        assert!(parse_bash("#4102444800\ncd /tmp\n").is_err());
+        assert!(parse_fish("- cmd: cd /tmp\n  when: 4102444800\n").is_err());
+    }
+
+    #[test]
+    fn split_words() {
+        let literal = |w: &str| Word::Literal(w.to_string());
+
+        assert_eq!(
+            split_commands("cd 'my dir' && ls a\\ b;echo \"$HOME\" x*"),
+            vec![
+                vec![literal("cd"), literal("my dir")],
+                vec![literal("ls"), literal("a b")],
+                vec![literal("echo"), Word::Expanded, Word::Expanded],
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_directories() {
+        let visits = replayed(
+            &[
+                "cd relative",
+                "cd /srv",
+                "cd www/../app && make",
+                "cd -",
+                "cd",
+                "sudo cd ~/notes",
+                "cd $DIR",
+                "cd sub",
+                "vim file.txt",
+            ],
+            HistoryTarget::Directories,
+        );
+
+        assert_eq!(
+            visits,
+            vec![
+                ("/srv".to_string(), 1.0),
+                ("/srv/app".to_string(), 2.0),
+                ("/srv".to_string(), 3.0),
+                ("/home/user".to_string(), 4.0),
+                ("/home/user/notes".to_string(), 5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_files() {
+        let visits = replayed(
+            &[
+                "vim unknown.txt",
+                "cd /srv",
+                "nvim -O a.rs ../b.rs +10",
+                "code ~/notes.md",
+            ],
+            HistoryTarget::Files,
+        );
+
+        assert_eq!(
+            visits,
+            vec![
+                ("/srv/a.rs".to_string(), 2.0),
+                ("/b.rs".to_string(), 2.0),
+                ("/home/user/notes.md".to_string(), 3.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+mod autojump;
+mod csv;
+mod history;
+mod z;
+mod zoxide;
+
+use super::current_time_secs;
+use super::store::FrecencyStore;
+use super::timestamp::format_rfc3339;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use directories::BaseDirs;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// The formats that can be imported with `--import`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ImportKind {
+    /// autojump's `autojump.txt`, with lines of `weight<TAB>path`
+    Autojump,
+    /// z or zsh-z's `~/.z`, with lines of `path|rank|time`
+    Z,
+    /// fasd's `~/.fasd`, with lines of `path|rank|time`
+    Fasd,
+    /// zoxide's `db.zo`
+    Zoxide,
+    /// zsh's history written with `setopt EXTENDED_HISTORY`
+    ZshHistory,
+    /// bash's history written with `HISTTIMEFORMAT` set
+    BashHistory,
+    /// fish's history
+    FishHistory,
+    /// Comma-separated values as written by `--export csv`
+    Csv,
+}
+
+impl ImportKind {
+    /// Parse the name of an import format, ignoring case
+    pub fn parse(name: &str) -> Result<ImportKind> {
+        ImportKind::from_str(name, true).map_err(|_| {
+            let names: Vec<String> = ImportKind::value_variants()
+                .iter()
+                .filter_map(|k| k.to_possible_value())
+                .map(|v| v.get_name().to_string())
+                .collect();
+            anyhow!(
+                "invalid import format '{}' (expected one of {})",
+                name,
+                names.join(", ")
+            )
+        })
+    }
+}
+
+/// An item read from another tool's database, mapped onto fre's statistics
+#[derive(Debug, PartialEq)]
+pub struct ImportedItem {
+    pub item: String,
+    /// The weight of every visit to the item, as of `last_access`
+    pub weight: f64,
+    pub num_accesses: i32,
+    /// Time of the last access in seconds since the epoch
+    pub last_access: f64,
+}
+
+impl ImportedItem {
+    /// Create an imported item, checking that its weight is a finite number and
+    /// that `last_access` is a valid access time as checked by `check_time`
+    pub fn new(
+        item: String,
+        weight: f64,
+        num_accesses: i32,
+        last_access: f64,
+    ) -> Result<ImportedItem> {
+        if !weight.is_finite() {
+            bail!("invalid weight {}", weight);
+        }
+        Ok(ImportedItem {
+            item,
+            weight,
+            num_accesses,
+            last_access: check_time(last_access)?,
+        })
+    }
+}
+
+pub use history::HistoryTarget;
+
+/// Read every item from the database of kind `kind` at `path`. For shell histories,
+/// every visit to a path of kind `target` is read as a separate item. Scores that are
+/// already decayed to the current time are converted with `half_life`.
+pub fn read_items(
+    kind: ImportKind,
+    path: &Path,
+    target: HistoryTarget,
+    half_life: f64,
+) -> Result<Vec<ImportedItem>> {
+    let data = fs::read(path).with_context(|| format!("failed to read {:?}", path))?;
+    let replay = |entries: Result<Vec<history::HistoryEntry>>| -> Result<Vec<ImportedItem>> {
+        let dirs = BaseDirs::new().ok_or_else(|| anyhow!("failed to determine home directory"))?;
+        Ok(history::replay(&entries?, dirs.home_dir(), target))
+    };
+
+    let items = match kind {
+        ImportKind::Autojump => {
+            // autojump does not store access times, so use the last time the file was written
+            let modified = fs::metadata(path)?
+                .modified()?
+                .duration_since(SystemTime::UNIX_EPOCH)?
+                .as_secs_f64();
+            autojump::parse(&String::from_utf8(data)?, modified)
+        }
+        ImportKind::Z | ImportKind::Fasd => z::parse(&String::from_utf8(data)?),
+        ImportKind::Zoxide => zoxide::parse(&data),
+        ImportKind::ZshHistory => replay(history::parse_zsh(&data)),
+        ImportKind::BashHistory => replay(history::parse_bash(&String::from_utf8_lossy(&data))),
+        ImportKind::FishHistory => replay(history::parse_fish(&String::from_utf8_lossy(&data))),
+        ImportKind::Csv => csv::parse(&String::from_utf8(data)?, half_life, current_time_secs()),
+    };
+
+    items.with_context(|| format!("failed to parse {:?}", path))
+}
+
+/// Add every imported item to `store`
+pub fn import(store: &mut FrecencyStore, items: &[ImportedItem]) -> Result<()> {
+    for item in items {
+        store.record(&item.item, item.weight, item.num_accesses, item.last_access)?;
+    }
+    Ok(())
+}
+
+/// Map a rank that counts visits, as used by z, fasd and zoxide, onto fre's statistics
+fn from_rank(item: String, rank: f64, last_access: f64) -> Result<ImportedItem> {
+    ImportedItem::new(item, rank, rank.round().max(1.0) as i32, last_access)
+}
+
+/// Check that an access time in seconds since the epoch read from another tool's
+/// database is a finite number and not in the future
+fn check_time(time: f64) -> Result<f64> {
+    if !time.is_finite() {
+        bail!("invalid time {}", time);
+    }
+    if time > current_time_secs() {
+        bail!("time {} is in the future", format_rfc3339(time));
+    }
+    Ok(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_kind() {
+        assert_eq!(ImportKind::parse("zoxide").unwrap(), ImportKind::Zoxide);
+        assert_eq!(ImportKind::parse("Z").unwrap(), ImportKind::Z);
+
+        let err = ImportKind::parse("jump").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "invalid import format 'jump' (expected one of autojump, z, fasd, zoxide, \
+             zsh-history, bash-history, fish-history, csv)"
+        );
+    }
+
+    #[test]
+    fn import_merges() {
+        let t = current_time_secs();
+        let mut store = FrecencyStore::default();
+        store.add("/home").unwrap();
+
+        import(
+            &mut store,
+            &[
+                from_rank("/home".to_string(), 3.0, t - 10.0).unwrap(),
+                from_rank("/tmp".to_string(), 0.4, t).unwrap(),
+            ],
+        )
+        .unwrap();
+
+        let items: Vec<(&str, i32)> = store
+            .items
+            .iter()
+            .map(|i| (i.item.as_str(), i.num_accesses))
+            .collect();
+        assert_eq!(items, vec![("/home", 4), ("/tmp", 1)]);
+        assert!((store.items[0].get_frecency(t) - 4.0).abs() < 0.01);
+        assert!((store.items[0].last_access() - t).abs() < 1.0);
+    }
+}
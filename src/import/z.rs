@@ -0,0 +1,75 @@
+use super::{from_rank, ImportedItem};
+use anyhow::{anyhow, Context, Result};
+
+/// Parse a z, zsh-z or fasd data file, with lines of the form `path|rank|time`
+pub fn parse(data: &str) -> Result<Vec<ImportedItem>> {
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| parse_line(line).with_context(|| format!("line {}", idx + 1)))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Result<ImportedItem> {
+    // Paths may contain '|', so split from the right
+    let mut fields = line.rsplitn(3, '|');
+    let time = fields.next().unwrap_or_default();
+    let rank = fields.next().ok_or_else(|| anyhow!("missing rank"))?;
+    let path = fields.next().ok_or_else(|| anyhow!("missing path"))?;
+
+    let rank: f64 = rank
+        .parse()
+        .map_err(|_| anyhow!("invalid rank '{}'", rank))?;
+    let time: f64 = time
+        .parse()
+        .map_err(|_| anyhow!("invalid time '{}'", time))?;
+
+    from_rank(path.to_string(), rank, time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lines() {
+        let data = "/home/user|12|1703721600\n\n/with|pipe|0.5|1703721000\n";
+
+        let items = parse(data).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                ImportedItem {
+                    item: "/home/user".to_string(),
+                    weight: 12.0,
+                    num_accesses: 12,
+                    last_access: 1703721600.0,
+                },
+                ImportedItem {
+                    item: "/with|pipe".to_string(),
+                    weight: 0.5,
+                    num_accesses: 1,
+                    last_access: 1703721000.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("/home|1|1\n/home|x|1\n").unwrap_err();
+
+        assert_eq!(format!("{:#}", err), "line 2: invalid rank 'x'");
+        assert!(parse("/home\n").is_err());
+
+        let err = parse("/home|1|4102444800\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "line 1: time 2100-01-01T00:00:00Z is in the future"
+        );
+        let err = parse("/home|1|1\n/tmp|inf|1\n").unwrap_err();
+        assert_eq!(format!("{:#}", err), "line 2: invalid weight inf");
+        assert!(parse("/home|1|NaN\n").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use super::{from_rank, ImportedItem};
+use anyhow::{anyhow, bail, Context, Result};
+
+/// The version of zoxide's database format that can be read
+const VERSION: u32 = 3;
+
+/// Parse a zoxide database. The database is encoded with bincode as a `u32`
+/// version followed by a list of `(path: String, rank: f64, last_accessed: u64)`,
+/// with integers in little endian and lengths as `u64`.
+pub fn parse(data: &[u8]) -> Result<Vec<ImportedItem>> {
+    let mut reader = Reader { data };
+
+    let version = u32::from_le_bytes(reader.take()?);
+    if version != VERSION {
+        bail!(
+            "unsupported zoxide database version {} (expected {})",
+            version,
+            VERSION
+        );
+    }
+
+    let len = reader.len()?;
+    let mut items = Vec::new();
+    for idx in 0..len {
+        let path_len = reader.len()?;
+        let path = String::from_utf8(reader.take_slice(path_len)?.to_vec())?;
+        let rank = f64::from_le_bytes(reader.take()?);
+        let last_accessed = u64::from_le_bytes(reader.take()?);
+        let item = from_rank(path, rank, last_accessed as f64)
+            .with_context(|| format!("entry {}", idx + 1))?;
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take_slice(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.data.len() < n {
+            bail!("unexpected end of zoxide database");
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take_slice(N)?.try_into()?)
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let len = u64::from_le_bytes(self.take()?);
+        usize::try_from(len).map_err(|_| anyhow!("invalid length {} in zoxide database", len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(dirs: &[(&str, f64, u64)]) -> Vec<u8> {
+        let mut data = VERSION.to_le_bytes().to_vec();
+        data.extend((dirs.len() as u64).to_le_bytes());
+        for (path, rank, last_accessed) in dirs {
+            data.extend((path.len() as u64).to_le_bytes());
+            data.extend(path.as_bytes());
+            data.extend(rank.to_le_bytes());
+            data.extend(last_accessed.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn parse_database() {
+        let data = encode(&[("/home/user", 4.5, 1703721600), ("/", 1.0, 1703721000)]);
+
+        let items = parse(&data).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                ImportedItem {
+                    item: "/home/user".to_string(),
+                    weight: 4.5,
+                    num_accesses: 5,
+                    last_access: 1703721600.0,
+                },
+                ImportedItem {
+                    item: "/".to_string(),
+                    weight: 1.0,
+                    num_accesses: 1,
+                    last_access: 1703721000.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let mut data = encode(&[("/home/user", 4.5, 1703721600)]);
+        data.truncate(data.len() - 1);
+        assert!(parse(&data).is_err());
+
+        let data = encode(&[("/", 1.0, 1), ("/home/user", f64::NAN, 1)]);
+        let err = parse(&data).unwrap_err();
+        assert_eq!(format!("{:#}", err), "entry 2: invalid weight NaN");
+
+        let mut data = encode(&[]);
+        data[0] = 2;
+        assert!(parse(&data).is_err());
+    }
+}
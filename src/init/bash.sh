@@ -0,0 +1,36 @@
+# fre shell integration for bash. Add to ~/.bashrc:
+#
+#     eval "$(fre init bash)"
+#
+# Set FRE_DISABLE to 1, true or yes to stop adding directories, and to 0, false, no or
+# nothing to add them again.
+
+# Add the current directory to the store in the background when it changes
+__fre_hook() {
+    local ret=$?
+    if [[ ! "${FRE_DISABLE:-}" =~ ^(1|true|yes)$ && "${__fre_last_pwd:-}" != "$PWD" ]]; then
+        __fre_last_pwd="$PWD"
+        (command fre --add -- "$PWD" >/dev/null 2>&1 &)
+    fi
+    return $ret
+}
+
+if [[ ";${PROMPT_COMMAND:-};" != *";__fre_hook;"* ]]; then
+    PROMPT_COMMAND="__fre_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
+
+# Jump to a directory chosen with fzf, filtered by the arguments, or with `fre --pick`
+fcd() {
+    local dir
+    if command -v fzf >/dev/null 2>&1; then
+        dir="$(command fre --sorted | fzf --no-sort --select-1 --exit-0 --query "$*")"
+    else
+        dir="$(command fre --pick)"
+    fi
+    [[ -n "$dir" ]] && cd -- "$dir"
+}
+
+# Alt-J runs fcd
+if [[ $- == *i* ]]; then
+    bind '"\ej": "\C-a\C-kfcd\C-m"'
+fi
@@ -0,0 +1,29 @@
+# fre shell integration for fish. Add to ~/.config/fish/config.fish:
+#
+#     fre init fish | source
+#
+# Set FRE_DISABLE to 1, true or yes to stop adding directories, and to 0, false, no or
+# nothing to add them again.
+
+# Add the current directory to the store in the background when it changes
+function __fre_hook --on-variable PWD
+    contains -- "$FRE_DISABLE" 1 true yes; and return
+    command fre --add -- $PWD >/dev/null 2>&1 &
+    disown 2>/dev/null
+end
+
+# Jump to a directory chosen with fzf, filtered by the arguments, or with `fre --pick`
+function fcd --description 'Jump to a directory chosen from fre'
+    set -l dir
+    if type -q fzf
+        set dir (command fre --sorted | fzf --no-sort --select-1 --exit-0 --query "$argv")
+    else
+        set dir (command fre --pick)
+    end
+    test -n "$dir"; and cd -- $dir
+end
+
+# Alt-J runs fcd
+if status is-interactive
+    bind \ej 'fcd; commandline -f repaint'
+end
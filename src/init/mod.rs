@@ -0,0 +1,23 @@
+use clap::ValueEnum;
+
+/// The shells `fre init` can print an integration script for
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Nushell,
+    Elvish,
+}
+
+/// Return a script that adds each directory changed to in `shell` to the store,
+/// and defines `fcd` to jump to a stored directory, bound to Alt-J
+pub fn script(shell: Shell) -> &'static str {
+    match shell {
+        Shell::Bash => include_str!("bash.sh"),
+        Shell::Zsh => include_str!("zsh.zsh"),
+        Shell::Fish => include_str!("fish.fish"),
+        Shell::Nushell => include_str!("nushell.nu"),
+        Shell::Elvish => include_str!("elvish.elv"),
+    }
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use std::io::BufRead;
+
+/// Read items separated by `terminator` from `reader`, skipping empty items
+pub fn read_items<R: BufRead>(mut reader: R, terminator: u8) -> Result<Vec<String>> {
+    let mut items = Vec::new();
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        if reader.read_until(terminator, &mut buf)? == 0 {
+            break;
+        }
+        if buf.last() == Some(&terminator) {
+            buf.pop();
+        }
+        if buf.is_empty() {
+            continue;
+        }
+
+        let item = String::from_utf8(buf.clone())
+            .with_context(|| format!("item {} is not valid UTF-8", items.len() + 1))?;
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_newline_separated() {
+        let input = "/home\n/home/a b\n\n/tmp".as_bytes();
+
+        let items = read_items(input, b'\n').unwrap();
+
+        assert_eq!(items, vec!["/home", "/home/a b", "/tmp"]);
+    }
+
+    #[test]
+    fn read_null_separated() {
+        let input = "/home/with\nnewline\0/tmp\0".as_bytes();
+
+        let items = read_items(input, b'\0').unwrap();
+
+        assert_eq!(items, vec!["/home/with\nnewline", "/tmp"]);
+    }
+
+    #[test]
+    fn read_invalid_utf8() {
+        let input: &[u8] = b"/home\n/bad\xff\n";
+
+        assert!(read_items(input, b'\n').is_err());
+    }
+}
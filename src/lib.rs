@@ -4,8 +4,21 @@ extern crate serde_derive;
 use std::time::SystemTime;
 
 pub mod args;
+pub mod batch;
+pub mod completions;
+pub mod config;
+pub mod explain;
+pub mod export;
+pub mod import;
+pub mod init;
+pub mod input;
+pub mod operation;
+pub mod output;
+pub mod pick;
 pub mod stats;
 pub mod store;
+pub mod subtree;
+pub mod timestamp;
 
 /// Return the current time in seconds as a float
 pub fn current_time_secs() -> f64 {
@@ -14,13 +27,3 @@ pub fn current_time_secs() -> f64 {
         .expect("failed to get system time")
         .as_secs_f64()
 }
-
-#[macro_export]
-macro_rules! error_and_exit {
-    ($($arg:tt)*) => {
-        {
-            error!($($arg)*);
-            ::std::process::exit(1);
-        }
-    };
-}
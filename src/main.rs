@@ -1,22 +1,75 @@
-use std::io::{stdout, BufWriter};
+use std::io::{stdin, stdout, BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::process::ExitCode;
 
-use anyhow::{Context, Result};
-use clap::Parser;
-use fre::{args::Cli, store::write_stats, *};
+use anyhow::{anyhow, bail, Context, Result};
+use fre::{
+    args::{Cli, Command, OutputFormat, Show, SortMethod},
+    config::Config,
+    operation::{
+        Annotation, GcOptions, Items, ListOptions, ListStyle, NamespaceOperation, Operation, Update,
+    },
+    store::{write_stats, FrecencyStore},
+    *,
+};
 
-fn main() -> Result<()> {
-    let args = Cli::try_parse()?;
+fn main() -> Result<ExitCode> {
+    // Usage errors exit with status 2 and runtime errors with status 1
+    let args = Cli::parse_command_line();
+
+    // Print a shell integration or completion script without touching the store
+    match args.command {
+        Some(Command::Init { shell }) => {
+            print!("{}", init::script(shell));
+            return Ok(ExitCode::SUCCESS);
+        }
+        Some(Command::Completions { shell }) => {
+            completions::write_completions(&mut stdout().lock(), shell)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        _ => {}
+    }
+
+    // Layer the command line over the config file to find the effective settings
+    let config = Config::load(&args)?;
+    let sort_method = config.sort_method.value;
 
     // Construct the path to the store file
-    let store_file = args::get_store_path(&args)?;
+    let store_file = config.store.value.clone();
 
     // Attempt to read and unmarshal the store file
-    let mut usage = store::read_store(&store_file)
+    let mut namespaces = store::read_store(&store_file)
         .with_context(|| format!("failed to read store file {:?}", &store_file))?;
 
+    // Namespace operations act on the whole store file rather than a single namespace
+    let operation = args.operation();
+    if let Operation::Namespace(op) = &operation {
+        match op {
+            NamespaceOperation::List => {
+                let mut w = BufWriter::new(stdout().lock());
+                for name in namespaces.names() {
+                    write!(w, "{}{}", name, args.terminator())?;
+                }
+                return Ok(ExitCode::SUCCESS);
+            }
+            NamespaceOperation::Copy { from, to } => namespaces.copy(from, to)?,
+            NamespaceOperation::Rename { from, to } => namespaces.rename(from, to)?,
+            NamespaceOperation::Delete(name) => namespaces.delete(name)?,
+        }
+        store::write_store(namespaces, &store_file).context("writing store")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Work on the selected namespace, which is only added to the store file if it gets items
+    let namespace = &config.namespace.value;
+    let existed = namespaces.names().contains(&namespace.as_str());
+    let mut usage = namespaces.take(namespace).unwrap_or_default();
+
     // If a new half life is defined, parse and set it
-    if let Some(h) = args.janitor.halflife {
-        usage.set_half_life(h);
+    if let Some(h) = config.half_life_for(!existed) {
+        if h != usage.half_life() {
+            usage.set_half_life(h);
+        }
     }
 
     // TODO write a test for this
@@ -24,61 +77,324 @@ fn main() -> Result<()> {
         usage.reset_time()
     }
 
-    // Print the directories if --sorted or --stat are specified
-    if args.stats.sorted || args.stats.stat {
-        let sorted = usage.sorted(args.sort_method);
-        let mut sorted = sorted.as_slice();
-        if let Some(l) = args.stats.limit {
-            sorted = &sorted[..usize::min(sorted.len(), l)]
+    let mut picked = true;
+    match operation {
+        // Print the items if --sorted, --stat or --tree, or list or stat are specified
+        Operation::List(mut options) => {
+            config.fill_list_options(&mut options);
+            list(&usage, &options, sort_method, args.terminator())?;
         }
 
-        let stdout = stdout();
-        let handle = stdout.lock();
-        let mut w = BufWriter::new(handle);
-        write_stats(
-            &mut w,
-            sorted,
-            args.sort_method,
-            args.stats.stat,
-            current_time_secs(),
-            args.stats.stat_digits,
-        )?;
-    }
+        // Interactively choose an item if --pick is specified
+        Operation::Pick { limit } => {
+            match pick::run(&mut usage, sort_method, limit.or(config.limit.value))? {
+                Some(item) => print!("{}{}", item, args.terminator()),
+                None => picked = false,
+            }
+        }
 
-    // Increment a directory
-    if args.updates.add {
-        usage.add(args.item.as_ref().expect("add requires an item"));
-    }
+        // Print the aliased item, or else the best match, without counting it as a visit
+        Operation::Best(query) => match pick::best(&usage, &query, sort_method) {
+            Some(item) => print!("{}{}", item, args.terminator()),
+            None => bail!("no item matches '{}'", query),
+        },
 
-    // Handle increasing or decreasing a directory's score by a given weight
-    if args.updates.increase.is_some() || args.updates.decrease.is_some() {
-        // Get a positive weight if increase, negative if decrease
-        let weight = match (args.updates.increase, args.updates.decrease) {
-            (Some(i), None) => i,
-            (None, Some(d)) => -d,
-            _ => panic!("increase and decrease cannot both be set"), // enforced by clap and block guard
-        };
+        Operation::Update {
+            update,
+            items,
+            at,
+            kind,
+        } => {
+            let items = read_items(items, args.terminator())?;
 
-        usage.adjust(
-            args.item
-                .as_ref()
-                .expect("item is required for increase or decrease"),
-            weight,
-        );
-    }
+            // Updates happen now unless a time is specified
+            let update_time = at.unwrap_or_else(current_time_secs);
 
-    // Delete a directory
-    if args.updates.delete {
-        usage.delete(&args.item.expect("delete requires an item"));
+            for item in &items {
+                let item = config.normalize(item, kind);
+                match update {
+                    Update::Delete => usage.delete(&item),
+                    _ if config.is_ignored(&item) => {}
+                    Update::Add => {
+                        usage.add_at(&item, update_time)?;
+                        usage.set_kind(&item, kind);
+                    }
+                    Update::Adjust(weight) => {
+                        usage.adjust_at(&item, weight, update_time)?;
+                        usage.set_kind(&item, kind);
+                    }
+                }
+            }
+        }
+
+        Operation::Annotate { annotation, items } => {
+            let items: Vec<String> = read_items(items, args.terminator())?
+                .iter()
+                .map(|item| config.normalize(item, None))
+                .collect();
+
+            // Change nothing unless every item is in the store
+            let missing: Vec<&str> = items
+                .iter()
+                .filter(|item| !usage.contains(item))
+                .map(String::as_str)
+                .collect();
+            if !missing.is_empty() {
+                bail!("not in the store: {}", missing.join(", "));
+            }
+
+            // Aliases must be unique within the namespace
+            if let Annotation::SetAlias(alias) = &annotation {
+                for item in &items {
+                    usage.check_alias(alias, item)?;
+                }
+            }
+
+            for item in &items {
+                let Some(stats) = usage.item_mut(item) else {
+                    continue;
+                };
+                match &annotation {
+                    Annotation::AddTag(tag) => {
+                        stats.tags.insert(tag.clone());
+                    }
+                    Annotation::RemoveTag(tag) => {
+                        stats.tags.remove(tag);
+                    }
+                    Annotation::SetMeta(key, value) => {
+                        stats.metadata.insert(key.clone(), value.clone());
+                    }
+                    Annotation::UnsetMeta(key) => {
+                        stats.metadata.remove(key);
+                    }
+                    Annotation::SetAlias(alias) => stats.alias = Some(alias.clone()),
+                    Annotation::UnsetAlias => stats.alias = None,
+                    Annotation::SetLabel(label) => stats.label = Some(label.clone()),
+                    Annotation::UnsetLabel => stats.label = None,
+                    Annotation::SetKind(kind) => stats.kind = Some(*kind),
+                    Annotation::UnsetKind => stats.kind = None,
+                }
+            }
+        }
+
+        // Apply every operation read from stdin before writing the store once
+        Operation::Batch => {
+            let mut operations = batch::read_operations(stdin().lock(), args.terminator() as u8)?;
+            for (line, op) in operations.iter_mut() {
+                let item = config.normalize(op.item(), None);
+                *op.item_mut() = item;
+                let is_delete = matches!(op, batch::Operation::Delete { .. });
+                if !is_delete && config.is_ignored(op.item()) {
+                    continue;
+                }
+                op.apply(&mut usage)
+                    .map_err(|e| anyhow!("line {}: {}", line, e))?;
+                if !is_delete {
+                    usage.set_kind(op.item(), None);
+                }
+            }
+        }
+
+        // Import items from another tool's database
+        Operation::Import {
+            kind,
+            path,
+            files,
+            preview,
+        } => {
+            let kind = import::ImportKind::parse(&kind)?;
+            let target = if files {
+                import::HistoryTarget::Files
+            } else {
+                import::HistoryTarget::Directories
+            };
+            let mut items = import::read_items(kind, &path, target, usage.half_life())?;
+            items.retain(|i| !config.is_ignored(&i.item));
+            import::import(&mut usage, &items)?;
+
+            // Print the top imported items and exit without writing the store
+            if let Some(n) = preview {
+                let mut sorted = usage.listed(sort_method);
+                sorted.truncate(n);
+                write_stats(
+                    &mut BufWriter::new(stdout().lock()),
+                    &sorted,
+                    sort_method,
+                    true,
+                    current_time_secs(),
+                    None,
+                    args.terminator(),
+                )?;
+                return Ok(ExitCode::SUCCESS);
+            }
+        }
+
+        // Print the store in another tool's format
+        Operation::Export(kind) => {
+            let mut w = BufWriter::new(stdout().lock());
+            let sorted = usage.sorted(sort_method);
+            export::write_export(&mut w, &sorted, kind, current_time_secs())?;
+        }
+
+        Operation::Gc(options) => gc(&mut usage, &options, sort_method),
+
+        Operation::SetHalfLife(h) => usage.set_half_life(h),
+
+        Operation::ShowConfig => {
+            let mut w = stdout().lock();
+            config.write(&mut w, usage.half_life())?;
+            writeln!(w, "items\t{}", usage.items.len())?;
+        }
+
+        Operation::Explain(item) => {
+            let item = config.normalize(&item, None);
+            let mut w = BufWriter::new(stdout().lock());
+            explain::write_explanation(&mut w, &usage, &item, current_time_secs())?;
+        }
+
+        // Handled before the namespace is read
+        Operation::Namespace(_) | Operation::None => {}
     }
 
     // Truncate store to top N directories
     if let Some(n) = args.janitor.truncate {
-        usage.truncate(n, args.sort_method);
+        usage.truncate(n, sort_method);
     }
 
     // Write the updated store file
-    store::write_store(usage, &store_file).context("writing store")?;
+    if existed || !usage.items.is_empty() {
+        namespaces.insert(namespace, usage);
+    }
+    store::write_store(namespaces, &store_file).context("writing store")?;
+
+    // Like fzf, signal that the picker was closed without choosing an item
+    if !picked {
+        return Ok(ExitCode::from(130));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Collect the items to update from stdin or the command line
+fn read_items(items: Items, terminator: char) -> Result<Vec<String>> {
+    match items {
+        Items::Stdin => input::read_items(stdin().lock(), terminator as u8)
+            .context("failed to read items from stdin"),
+        Items::Args(items) => Ok(items),
+    }
+}
+
+/// Print the items in the store as a list, with their scores, or as a tree
+fn list(
+    usage: &FrecencyStore,
+    options: &ListOptions,
+    sort_method: SortMethod,
+    terminator: char,
+) -> Result<()> {
+    let mut sorted = usage.listed(sort_method);
+    sorted.retain(|item| options.matches(item));
+    let is_tree = matches!(options.style, ListStyle::Tree { .. });
+    if let Some(dir) = &options.under {
+        let dir = subtree::absolute_dir(dir)
+            .with_context(|| format!("failed to resolve directory {:?}", dir))?;
+        sorted = subtree::under(sorted, &dir);
+        if options.rollup {
+            sorted = subtree::roll_up(&sorted, &dir);
+            store::sort_items(&mut sorted, sort_method);
+            store::pin_first(&mut sorted);
+        }
+        // The tree is always rooted at the directory given to --under
+        if options.relative || is_tree {
+            subtree::make_relative(&mut sorted, &dir);
+        }
+    }
+
+    let stdout = stdout();
+    let handle = stdout.lock();
+    let mut w = BufWriter::new(handle);
+
+    if let ListStyle::Tree { depth, threshold } = options.style {
+        let tree = subtree::TreeNode::build(&sorted, current_time_secs());
+        let tree_options = subtree::TreeOptions {
+            sort_method,
+            max_depth: depth,
+            threshold,
+            limit: options.limit,
+        };
+        return subtree::write_tree(&mut w, &tree, &tree_options);
+    }
+
+    if let Some(l) = options.limit {
+        sorted.truncate(l);
+    }
+
+    // Print labels in place of or beside the items in text output
+    if options.show != Show::Item
+        && options.output.unwrap_or(OutputFormat::Text) == OutputFormat::Text
+    {
+        for item in sorted.iter_mut() {
+            item.item = item.display_name(options.show);
+        }
+    }
+    let sorted = sorted.as_slice();
+
+    match (
+        &options.format,
+        options.output.unwrap_or(OutputFormat::Text),
+    ) {
+        (None, OutputFormat::Text) if options.all_scores => {
+            let table_options = output::table::TableOptions {
+                aligned: std::io::stdout().is_terminal(),
+                header: options.header,
+                totals: options.totals,
+                precision_override: options.stat_digits,
+            };
+            output::table::write_table(
+                &mut w,
+                sorted,
+                current_time_secs(),
+                &table_options,
+                terminator,
+            )
+        }
+        (Some(template), _) => output::template::write_template(
+            &mut w,
+            sorted,
+            template,
+            sort_method,
+            current_time_secs(),
+            terminator,
+        ),
+        (None, OutputFormat::Text) => write_stats(
+            &mut w,
+            sorted,
+            sort_method,
+            options.style == ListStyle::Stat,
+            current_time_secs(),
+            options.stat_digits,
+            terminator,
+        ),
+        (None, OutputFormat::Json) => output::json::write_json(&mut w, sorted, current_time_secs()),
+        (None, OutputFormat::Jsonl) => {
+            output::json::write_jsonl(&mut w, sorted, current_time_secs())
+        }
+    }
+}
 
-    Ok(())
+/// Remove the items selected by `options` from the store
+fn gc(usage: &mut FrecencyStore, options: &GcOptions, sort_method: SortMethod) {
+    if let Some(threshold) = options.threshold {
+        let now = current_time_secs();
+        usage.retain(|i| i.pinned || i.get_frecency(now) >= threshold);
+    }
+    if options.missing {
+        // Only directories, files and items of unknown kind can go missing
+        usage.retain(|i| {
+            let path = Path::new(&i.item);
+            i.pinned || i.kind.is_some_and(|k| !k.is_path()) || !path.is_absolute() || path.exists()
+        });
+    }
+    if let Some(n) = options.keep {
+        usage.truncate(n, sort_method);
+    }
 }
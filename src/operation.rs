@@ -0,0 +1,158 @@
+use crate::args::{OutputFormat, Show};
+use crate::export::ExportKind;
+use crate::output::template::Template;
+use crate::stats::{ItemStats, Kind};
+use std::path::PathBuf;
+
+/// What to do with the store, built from either a subcommand or the equivalent flags
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// Print the stored items
+    List(ListOptions),
+    /// Interactively pick an item, showing at most `limit` items
+    Pick { limit: Option<usize> },
+    /// Print the item with this alias, or else the best item fuzzy matching it
+    Best(String),
+    /// Add or remove a tag, metadata, an alias, a label or a kind on stored items
+    Annotate {
+        annotation: Annotation,
+        items: Items,
+    },
+    /// Add, adjust or delete items
+    Update {
+        update: Update,
+        items: Items,
+        /// Time of the update in seconds since the epoch, or now if `None`
+        at: Option<f64>,
+        /// The kind of the items, or `None` to guess it
+        kind: Option<Kind>,
+    },
+    /// Apply operations read from stdin
+    Batch,
+    /// Import another tool's database of kind `kind` at `path`
+    Import {
+        kind: String,
+        path: PathBuf,
+        files: bool,
+        /// Print this many of the top items instead of writing the store
+        preview: Option<usize>,
+    },
+    /// Print the store in the format of another tool's database
+    Export(ExportKind),
+    /// Remove items from the store
+    Gc(GcOptions),
+    /// Change the half life to this many seconds
+    SetHalfLife(f64),
+    /// Print the store's settings
+    ShowConfig,
+    /// Print how the scores of an item are calculated
+    Explain(String),
+    /// List, copy, rename or delete whole namespaces
+    Namespace(NamespaceOperation),
+    /// Only apply the janitor flags
+    None,
+}
+
+/// An operation on the namespaces of the store file
+#[derive(Debug, Clone, PartialEq)]
+pub enum NamespaceOperation {
+    List,
+    Copy { from: String, to: String },
+    Rename { from: String, to: String },
+    Delete(String),
+}
+
+/// A change to the items in the store
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Update {
+    Add,
+    /// Adjust the weight by a positive or negative amount
+    Adjust(f64),
+    Delete,
+}
+
+/// A change to the tags, metadata, alias, label or kind of an item
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    AddTag(String),
+    RemoveTag(String),
+    /// Set the metadata key to a value
+    SetMeta(String, String),
+    UnsetMeta(String),
+    SetAlias(String),
+    UnsetAlias,
+    SetLabel(String),
+    UnsetLabel,
+    SetKind(Kind),
+    UnsetKind,
+}
+
+/// Where to read the items to update from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Items {
+    Args(Vec<String>),
+    Stdin,
+}
+
+/// How to print the stored items
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListStyle {
+    /// Only the items, from highest to lowest score
+    Sorted,
+    /// Each item preceded by its score
+    Stat,
+    /// The items as a tree of path components
+    Tree {
+        depth: Option<usize>,
+        threshold: Option<f64>,
+    },
+}
+
+/// Options for printing the stored items
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListOptions {
+    pub style: ListStyle,
+    pub limit: Option<usize>,
+    pub under: Option<PathBuf>,
+    pub relative: bool,
+    pub rollup: bool,
+    /// The output format, or `None` to use the configured one
+    pub output: Option<OutputFormat>,
+    pub format: Option<Template>,
+    pub all_scores: bool,
+    pub header: bool,
+    pub totals: bool,
+    pub stat_digits: Option<usize>,
+    /// Only print items with every one of these tags
+    pub tags: Vec<String>,
+    /// Only print items with each of these metadata keys set to the value
+    pub metadata: Vec<(String, String)>,
+    /// What to print for each item in text output
+    pub show: Show,
+    /// Only print items of this kind
+    pub kind: Option<Kind>,
+}
+
+impl ListOptions {
+    /// Whether `item` has the kind, tags and metadata to be printed
+    pub fn matches(&self, item: &ItemStats) -> bool {
+        self.kind
+            .is_none_or(|kind| item.kind_or_infer() == Some(kind))
+            && self.tags.iter().all(|tag| item.tags.contains(tag))
+            && self
+                .metadata
+                .iter()
+                .all(|(key, value)| item.metadata.get(key) == Some(value))
+    }
+}
+
+/// Which items to remove from the store
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcOptions {
+    /// Keep only this many of the top items
+    pub keep: Option<usize>,
+    /// Remove unpinned items with a lower frecency than this
+    pub threshold: Option<f64>,
+    /// Remove unpinned items that are absolute paths which no longer exist
+    pub missing: bool,
+}
@@ -0,0 +1,134 @@
+use crate::stats::{ItemStats, Kind};
+use crate::timestamp::format_rfc3339;
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// The stable JSON representation of an item in `--output json` and `--output jsonl`
+#[derive(Serialize, Debug)]
+pub struct ItemRecord<'a> {
+    /// Position of the item in the sorted output, starting at 1
+    pub rank: usize,
+    pub item: &'a str,
+    /// Frecency decayed to the current time
+    pub frecency: f64,
+    pub num_accesses: i32,
+    /// Time of the last access in seconds since the epoch
+    pub last_accessed: f64,
+    /// Time of the last access as an RFC 3339 timestamp in UTC
+    pub last_accessed_rfc3339: String,
+    pub pinned: bool,
+    pub tags: &'a BTreeSet<String>,
+    pub metadata: &'a BTreeMap<String, String>,
+    pub alias: Option<&'a str>,
+    pub label: Option<&'a str>,
+    /// The item's kind, guessed if it was never set
+    pub kind: Option<Kind>,
+}
+
+impl<'a> ItemRecord<'a> {
+    pub fn new(rank: usize, item: &'a ItemStats, current_time: f64) -> ItemRecord<'a> {
+        ItemRecord {
+            rank,
+            item: &item.item,
+            frecency: item.get_frecency(current_time),
+            num_accesses: item.num_accesses,
+            last_accessed: item.last_access(),
+            last_accessed_rfc3339: format_rfc3339(item.last_access()),
+            pinned: item.pinned,
+            tags: &item.tags,
+            metadata: &item.metadata,
+            alias: item.alias.as_deref(),
+            label: item.label.as_deref(),
+            kind: item.kind_or_infer(),
+        }
+    }
+}
+
+/// Print the items as a single JSON array
+pub fn write_json<W: Write>(w: &mut W, items: &[ItemStats], current_time: f64) -> Result<()> {
+    let records: Vec<ItemRecord> = records(items, current_time).collect();
+    serde_json::to_writer_pretty(&mut *w, &records)?;
+    w.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Print the items as JSON objects, one per line
+pub fn write_jsonl<W: Write>(w: &mut W, items: &[ItemStats], current_time: f64) -> Result<()> {
+    for record in records(items, current_time) {
+        serde_json::to_writer(&mut *w, &record)?;
+        w.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn records(items: &[ItemStats], current_time: f64) -> impl Iterator<Item = ItemRecord<'_>> {
+    items
+        .iter()
+        .enumerate()
+        .map(move |(idx, item)| ItemRecord::new(idx + 1, item, current_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn create_items(t: f64) -> Vec<ItemStats> {
+        let mut first = ItemStats::new("/home/user\tweird\nname".to_string(), t, 100.0);
+        first.update_frecency(2.0);
+        first.update_num_accesses(2);
+        first.update_last_access(1703721600.0);
+        first.tags.insert("repo".to_string());
+        first
+            .metadata
+            .insert("project".to_string(), "fre".to_string());
+        first.alias = Some("weird".to_string());
+        let second = ItemStats::new("/".to_string(), t, 100.0);
+        vec![first, second]
+    }
+
+    #[test]
+    fn jsonl_fields() {
+        let t = crate::current_time_secs();
+        let mut b = Vec::new();
+
+        write_jsonl(&mut b, &create_items(t), t).unwrap();
+
+        let output = String::from_utf8(b).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["rank"], 1);
+        assert_eq!(lines[0]["item"], "/home/user\tweird\nname");
+        assert!((lines[0]["frecency"].as_f64().unwrap() - 2.0).abs() < 0.01);
+        assert_eq!(lines[0]["num_accesses"], 2);
+        assert_eq!(lines[0]["last_accessed"], 1703721600.0);
+        assert_eq!(lines[0]["last_accessed_rfc3339"], "2023-12-28T00:00:00Z");
+        assert_eq!(lines[0]["pinned"], false);
+        assert_eq!(lines[0]["tags"], serde_json::json!(["repo"]));
+        assert_eq!(lines[0]["metadata"]["project"], "fre");
+        assert_eq!(lines[0]["alias"], "weird");
+        assert_eq!(lines[0]["label"], Value::Null);
+        assert_eq!(lines[0]["kind"], Value::Null);
+        assert_eq!(lines[1]["kind"], "dir");
+        assert_eq!(lines[1]["tags"], serde_json::json!([]));
+        assert_eq!(lines[1]["rank"], 2);
+        assert_eq!(lines[1]["item"], "/");
+    }
+
+    #[test]
+    fn json_array() {
+        let t = crate::current_time_secs();
+        let mut b = Vec::new();
+
+        write_json(&mut b, &create_items(t), t).unwrap();
+
+        let output: Value = serde_json::from_slice(&b).unwrap();
+        let records = output.as_array().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["rank"], 2);
+    }
+}
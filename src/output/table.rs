@@ -0,0 +1,206 @@
+use crate::stats::ItemStats;
+use anyhow::Result;
+use std::io::Write;
+
+const HEADERS: [&str; 4] = ["FRECENT", "FREQUENT", "HOURS", "ITEM"];
+
+/// Options controlling how the table of scores is printed
+pub struct TableOptions {
+    /// Pad columns with spaces to line them up, rather than separating them with tabs
+    pub aligned: bool,
+    /// Print a header naming each column
+    pub header: bool,
+    /// Print a footer with the total frecency and accesses and the most recent access
+    pub totals: bool,
+    /// Override the number of digits shown for each score
+    pub precision_override: Option<usize>,
+}
+
+/// Print the frecent, frequent and recent scores of every item side by side,
+/// each row followed by `terminator`
+pub fn write_table<W: Write>(
+    w: &mut W,
+    items: &[ItemStats],
+    current_time: f64,
+    options: &TableOptions,
+    terminator: char,
+) -> Result<()> {
+    let format_score = |score: f64, default_precision: usize| {
+        format!(
+            "{:.prec$}",
+            score,
+            prec = options.precision_override.unwrap_or(default_precision)
+        )
+    };
+    let row = |frecency: f64, accesses: i32, hours: f64, item: String| {
+        [
+            format_score(frecency, 3),
+            format_score(accesses as f64, 0),
+            format_score(hours, 3),
+            item,
+        ]
+    };
+
+    let mut rows = Vec::new();
+    if options.header {
+        rows.push(HEADERS.map(String::from));
+    }
+    for item in items {
+        rows.push(row(
+            item.get_frecency(current_time),
+            item.num_accesses,
+            hours_since(item.last_access(), current_time),
+            item.item.clone(),
+        ));
+    }
+    if options.totals {
+        let last_access = items
+            .iter()
+            .map(ItemStats::last_access)
+            .fold(f64::NEG_INFINITY, f64::max);
+        rows.push(row(
+            items.iter().map(|i| i.get_frecency(current_time)).sum(),
+            items
+                .iter()
+                .map(|i| i.num_accesses)
+                .fold(0, i32::saturating_add),
+            if items.is_empty() {
+                0.0
+            } else {
+                hours_since(last_access, current_time)
+            },
+            format!("TOTAL ({} items)", items.len()),
+        ));
+    }
+
+    let mut widths = [0; 3];
+    if options.aligned {
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = usize::max(*width, cell.chars().count());
+            }
+        }
+    }
+
+    for [frecent, frequent, hours, item] in rows {
+        if options.aligned {
+            w.write_fmt(format_args!(
+                "{:>w0$}  {:>w1$}  {:>w2$}  {}{}",
+                frecent,
+                frequent,
+                hours,
+                item,
+                terminator,
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+            ))?;
+        } else {
+            w.write_fmt(format_args!(
+                "{}\t{}\t{}\t{}{}",
+                frecent, frequent, hours, item, terminator
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn hours_since(time: f64, current_time: f64) -> f64 {
+    (current_time - time) / 60.0 / 60.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_items(t: f64) -> Vec<ItemStats> {
+        let mut first = ItemStats::new("/home/user".to_string(), t, 1e12);
+        first.update_frecency(12.5);
+        first.update_num_accesses(120);
+        first.update_last_access(t - 60.0 * 60.0);
+        let mut second = ItemStats::new("/".to_string(), t, 1e12);
+        second.update_frecency(1.0);
+        second.update_num_accesses(3);
+        second.update_last_access(t - 2.0 * 60.0 * 60.0);
+        vec![first, second]
+    }
+
+    fn write_table_string(options: &TableOptions) -> String {
+        let t = crate::current_time_secs();
+        let mut b = Vec::new();
+        write_table(&mut b, &create_items(t), t, options, '\n').unwrap();
+        String::from_utf8(b).unwrap()
+    }
+
+    #[test]
+    fn totals_saturate() {
+        let t = crate::current_time_secs();
+        let mut items = create_items(t);
+        for item in items.iter_mut() {
+            item.update_num_accesses(i32::MAX);
+        }
+        let mut b = Vec::new();
+        let options = TableOptions {
+            aligned: false,
+            header: false,
+            totals: true,
+            precision_override: None,
+        };
+
+        write_table(&mut b, &items, t, &options, '\n').unwrap();
+
+        let table = String::from_utf8(b).unwrap();
+        assert!(table.ends_with(&format!("\t{}\t1.000\tTOTAL (2 items)\n", i32::MAX)));
+    }
+
+    #[test]
+    fn tab_separated() {
+        let table = write_table_string(&TableOptions {
+            aligned: false,
+            header: true,
+            totals: false,
+            precision_override: None,
+        });
+
+        assert_eq!(
+            table,
+            "FRECENT\tFREQUENT\tHOURS\tITEM\n\
+             12.500\t120\t1.000\t/home/user\n\
+             1.000\t3\t2.000\t/\n"
+        );
+    }
+
+    #[test]
+    fn aligned_with_totals() {
+        let table = write_table_string(&TableOptions {
+            aligned: true,
+            header: true,
+            totals: true,
+            precision_override: Some(1),
+        });
+
+        assert_eq!(
+            table,
+            "FRECENT  FREQUENT  HOURS  ITEM\n   \
+                12.5     120.0    1.0  /home/user\n    \
+                 1.0       3.0    2.0  /\n   \
+                13.5     123.0    1.0  TOTAL (2 items)\n"
+        );
+    }
+
+    #[test]
+    fn no_header() {
+        let table = write_table_string(&TableOptions {
+            aligned: true,
+            header: false,
+            totals: false,
+            precision_override: None,
+        });
+
+        assert_eq!(
+            table,
+            "12.500  120  1.000  /home/user\n 1.000    3  2.000  /\n"
+        );
+    }
+}
@@ -0,0 +1,524 @@
+use crate::args::{Show, SortMethod};
+use crate::stats::ItemStats;
+use crate::timestamp::format_rfc3339;
+use anyhow::{anyhow, bail, Result};
+use std::io::Write;
+
+/// A user-defined output format such as `{rank:>3} {frecency:.2} {item}`.
+///
+/// Fields are written as `{name}` or `{name:spec}`, where `spec` is either
+/// `[[fill]align][width][.precision]` with `align` one of `<`, `^` or `>` and `width`
+/// and `precision` at most `MAX_WIDTH`, or one of `epoch`, `relative` or `rfc3339` for
+/// `last_access`. Use `{{` and `}}` for literal braces, and `\t`, `\n`, `\0` and `\\`
+/// for escaped characters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(Field, Spec),
+}
+
+/// The values that can be printed in a template
+#[derive(Debug, Clone, PartialEq)]
+enum Field {
+    /// The item itself
+    Item,
+    /// Position in the sorted output, starting at 1
+    Rank,
+    /// The score used by the sort method, as printed by --stat
+    Score,
+    /// Frecency decayed to the current time
+    Frecency,
+    /// Number of accesses
+    Accesses,
+    /// Time of the last access
+    LastAccess,
+    /// Time since the last access in human units, like `3h`
+    Age,
+    /// Hours since the last access
+    Hours,
+    /// Frecency as a percentage of the total frecency of the printed items
+    Percent,
+    /// Whether the item is pinned
+    Pinned,
+    /// The item's tags, separated by commas
+    Tags,
+    /// The value of a metadata key, written `meta.KEY`
+    Meta(String),
+    /// The item's alias, or nothing
+    Alias,
+    /// The item's label, or the item if it has no label
+    Label,
+    /// The item's kind, guessed if it was never set, or nothing if it is not known
+    Kind,
+}
+
+impl Field {
+    fn parse(name: &str) -> Result<Field> {
+        Ok(match name {
+            "item" => Field::Item,
+            "rank" => Field::Rank,
+            "score" => Field::Score,
+            "frecency" => Field::Frecency,
+            "accesses" | "num_accesses" => Field::Accesses,
+            "last_access" => Field::LastAccess,
+            "age" => Field::Age,
+            "hours" => Field::Hours,
+            "percent" => Field::Percent,
+            "pinned" => Field::Pinned,
+            "tags" => Field::Tags,
+            "alias" => Field::Alias,
+            "label" => Field::Label,
+            "kind" => Field::Kind,
+            _ => match name.strip_prefix("meta.") {
+                Some(key) if !key.is_empty() => Field::Meta(key.to_string()),
+                _ => bail!("unknown field '{}'", name),
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TimeStyle {
+    Epoch,
+    Relative,
+    Rfc3339,
+}
+
+/// The largest width or precision a format spec can have
+const MAX_WIDTH: usize = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    width: usize,
+    precision: Option<usize>,
+    time_style: Option<TimeStyle>,
+}
+
+impl Default for Spec {
+    fn default() -> Spec {
+        Spec {
+            fill: ' ',
+            align: None,
+            width: 0,
+            precision: None,
+            time_style: None,
+        }
+    }
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Spec> {
+        let time_style = match spec {
+            "epoch" => Some(TimeStyle::Epoch),
+            "relative" => Some(TimeStyle::Relative),
+            "rfc3339" => Some(TimeStyle::Rfc3339),
+            _ => None,
+        };
+        if time_style.is_some() {
+            return Ok(Spec {
+                time_style,
+                ..Spec::default()
+            });
+        }
+
+        let mut result = Spec::default();
+        let chars: Vec<char> = spec.chars().collect();
+        let mut rest = &chars[..];
+        if let [fill, align, tail @ ..] = rest {
+            if let Some(align) = parse_align(*align) {
+                result.fill = *fill;
+                result.align = Some(align);
+                rest = tail;
+            }
+        }
+        if result.align.is_none() {
+            if let [align, tail @ ..] = rest {
+                if let Some(align) = parse_align(*align) {
+                    result.align = Some(align);
+                    rest = tail;
+                }
+            }
+        }
+
+        let rest: String = rest.iter().collect();
+        let (width, precision) = match rest.split_once('.') {
+            Some((width, precision)) => (width, Some(precision)),
+            None => (rest.as_str(), None),
+        };
+        let number = |n: &str| {
+            n.parse()
+                .ok()
+                .filter(|n| *n <= MAX_WIDTH)
+                .ok_or_else(|| anyhow!("invalid format spec '{}'", spec))
+        };
+        if !width.is_empty() {
+            result.width = number(width)?;
+        }
+        if let Some(precision) = precision {
+            result.precision = Some(number(precision)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Pad `value` to the width of the spec, aligning to `default_align` if the
+    /// spec does not specify an alignment
+    fn pad(&self, value: String, default_align: Align) -> String {
+        let len = value.chars().count();
+        if len >= self.width {
+            return value;
+        }
+
+        let padding = self.width - len;
+        let (before, after) = match self.align.unwrap_or(default_align) {
+            Align::Left => (0, padding),
+            Align::Right => (padding, 0),
+            Align::Center => (padding / 2, padding - padding / 2),
+        };
+        let fill = |n| std::iter::repeat_n(self.fill, n).collect::<String>();
+        format!("{}{}{}", fill(before), value, fill(after))
+    }
+}
+
+fn parse_align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '^' => Some(Align::Center),
+        '>' => Some(Align::Right),
+        _ => None,
+    }
+}
+
+impl Template {
+    /// Parse a template string, returning an error describing the first invalid field
+    pub fn parse(template: &str) -> Result<Template> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.as_str().starts_with('{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.as_str().starts_with('}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let rest = chars.as_str();
+                    let end = rest
+                        .find('}')
+                        .ok_or_else(|| anyhow!("unclosed '{{' in format"))?;
+                    let (name, spec) = match rest[..end].split_once(':') {
+                        Some((name, spec)) => (name, Spec::parse(spec)?),
+                        None => (&rest[..end], Spec::default()),
+                    };
+                    let field = Field::parse(name.trim())?;
+                    if spec.time_style.is_some() && field != Field::LastAccess {
+                        bail!("only last_access can be formatted as a time");
+                    }
+
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field, spec));
+                    chars = rest[end + 1..].chars();
+                }
+                '}' => bail!("unmatched '}}' in format"),
+                '\\' => match chars.next() {
+                    Some('t') => literal.push('\t'),
+                    Some('n') => literal.push('\n'),
+                    Some('0') => literal.push('\0'),
+                    Some('\\') => literal.push('\\'),
+                    Some(other) => {
+                        literal.push('\\');
+                        literal.push(other);
+                    }
+                    None => literal.push('\\'),
+                },
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Template { segments })
+    }
+
+    /// Render the template for a single item
+    pub fn render(&self, item: &ItemStats, rank: usize, context: &TemplateContext) -> String {
+        let mut out = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(s) => out.push_str(s),
+                Segment::Field(field, spec) => {
+                    out.push_str(&render_field(field, spec, item, rank, context))
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Values shared by every item rendered with a template
+pub struct TemplateContext {
+    pub sort_method: SortMethod,
+    pub current_time: f64,
+    /// Total frecency of the printed items, used for `{percent}`
+    pub total_frecency: f64,
+}
+
+impl TemplateContext {
+    pub fn new(items: &[ItemStats], sort_method: SortMethod, current_time: f64) -> Self {
+        TemplateContext {
+            sort_method,
+            current_time,
+            total_frecency: items.iter().map(|i| i.get_frecency(current_time)).sum(),
+        }
+    }
+}
+
+fn render_field(
+    field: &Field,
+    spec: &Spec,
+    item: &ItemStats,
+    rank: usize,
+    context: &TemplateContext,
+) -> String {
+    let float = |value: f64, default_precision: usize| {
+        let value = format!(
+            "{:.prec$}",
+            value,
+            prec = spec.precision.unwrap_or(default_precision)
+        );
+        spec.pad(value, Align::Right)
+    };
+    let age = context.current_time - item.last_access();
+
+    match field {
+        Field::Item => spec.pad(item.item.clone(), Align::Left),
+        Field::Rank => spec.pad(rank.to_string(), Align::Right),
+        Field::Score => match context.sort_method {
+            SortMethod::Recent => float(age / 60.0 / 60.0, 3),
+            SortMethod::Frequent => float(item.num_accesses as f64, 0),
+            SortMethod::Frecent => float(item.get_frecency(context.current_time), 3),
+        },
+        Field::Frecency => float(item.get_frecency(context.current_time), 3),
+        Field::Accesses => spec.pad(item.num_accesses.to_string(), Align::Right),
+        Field::LastAccess => match spec.time_style {
+            Some(TimeStyle::Relative) => format!("{} ago", human_duration(age)),
+            Some(TimeStyle::Rfc3339) => format_rfc3339(item.last_access()),
+            Some(TimeStyle::Epoch) | None => float(item.last_access(), 0),
+        },
+        Field::Age => spec.pad(human_duration(age), Align::Right),
+        Field::Hours => float(age / 60.0 / 60.0, 3),
+        Field::Percent => {
+            let percent = if context.total_frecency > 0.0 {
+                item.get_frecency(context.current_time) / context.total_frecency * 100.0
+            } else {
+                0.0
+            };
+            float(percent, 1)
+        }
+        Field::Pinned => spec.pad(item.pinned.to_string(), Align::Left),
+        Field::Tags => {
+            let tags: Vec<&str> = item.tags.iter().map(String::as_str).collect();
+            spec.pad(tags.join(","), Align::Left)
+        }
+        Field::Meta(key) => {
+            let value = item.metadata.get(key).map_or("", String::as_str);
+            spec.pad(value.to_string(), Align::Left)
+        }
+        Field::Alias => spec.pad(item.alias.clone().unwrap_or_default(), Align::Left),
+        Field::Label => spec.pad(item.display_name(Show::Label), Align::Left),
+        Field::Kind => {
+            let kind = item.kind_or_infer().map_or("", |k| k.name());
+            spec.pad(kind.to_string(), Align::Left)
+        }
+    }
+}
+
+/// Format a number of seconds in the largest whole unit, like `3h` or `2w`
+pub fn human_duration(secs: f64) -> String {
+    const UNITS: [(f64, &str); 5] = [
+        (60.0 * 60.0 * 24.0 * 365.0, "y"),
+        (60.0 * 60.0 * 24.0 * 7.0, "w"),
+        (60.0 * 60.0 * 24.0, "d"),
+        (60.0 * 60.0, "h"),
+        (60.0, "m"),
+    ];
+
+    let secs = secs.max(0.0);
+    for (unit_secs, unit) in UNITS {
+        if secs >= unit_secs {
+            return format!("{}{}", (secs / unit_secs).floor(), unit);
+        }
+    }
+    format!("{}s", secs.floor())
+}
+
+/// Print every item with `template`, each followed by `terminator`
+pub fn write_template<W: Write>(
+    w: &mut W,
+    items: &[ItemStats],
+    template: &Template,
+    sort_method: SortMethod,
+    current_time: f64,
+    terminator: char,
+) -> Result<()> {
+    let context = TemplateContext::new(items, sort_method, current_time);
+    for (idx, item) in items.iter().enumerate() {
+        w.write_fmt(format_args!(
+            "{}{}",
+            template.render(item, idx + 1, &context),
+            terminator
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::Kind;
+
+    fn create_item(t: f64) -> ItemStats {
+        let mut item = ItemStats::new("/test/item".to_string(), t, 1e12);
+        item.update_frecency(1.5);
+        item.update_num_accesses(3);
+        item.update_last_access(t - 2.0 * 60.0 * 60.0);
+        item.tags.insert("repo".to_string());
+        item.tags.insert("work".to_string());
+        item.metadata
+            .insert("host".to_string(), "laptop".to_string());
+        item.label = Some("test".to_string());
+        item.kind = Some(Kind::File);
+        item
+    }
+
+    fn render(template: &str, sort_method: SortMethod) -> String {
+        let t = 1703721600.0;
+        let item = create_item(t);
+        let context = TemplateContext {
+            sort_method,
+            current_time: t,
+            total_frecency: 6.0,
+        };
+        Template::parse(template)
+            .unwrap()
+            .render(&item, 7, &context)
+    }
+
+    #[test]
+    fn render_fields() {
+        assert_eq!(
+            render(
+                "{rank:>3} {frecency:.2} {accesses} {last_access:relative} {item}",
+                SortMethod::Frecent
+            ),
+            "  7 1.50 3 2h ago /test/item"
+        );
+        assert_eq!(
+            render("{hours:.1}|{age}|{percent}%|{pinned}", SortMethod::Frecent),
+            "2.0|2h|25.0%|false"
+        );
+        assert_eq!(
+            render("{last_access:rfc3339} {last_access}", SortMethod::Frecent),
+            "2023-12-27T22:00:00Z 1703714400"
+        );
+        assert_eq!(
+            render("{tags}|{meta.host}|{meta.project:>3}", SortMethod::Frecent),
+            "repo,work|laptop|   "
+        );
+        assert_eq!(
+            render("{alias}|{label}|{kind}", SortMethod::Frecent),
+            "|test|file"
+        );
+    }
+
+    #[test]
+    fn render_score_by_sort_method() {
+        assert_eq!(render("{score}", SortMethod::Frecent), "1.500");
+        assert_eq!(render("{score}", SortMethod::Frequent), "3");
+        assert_eq!(render("{score}", SortMethod::Recent), "2.000");
+    }
+
+    #[test]
+    fn render_alignment() {
+        assert_eq!(
+            render("[{item:<12}]", SortMethod::Frecent),
+            "[/test/item  ]"
+        );
+        assert_eq!(
+            render("[{item:>12}]", SortMethod::Frecent),
+            "[  /test/item]"
+        );
+        assert_eq!(
+            render("[{item:*^14}]", SortMethod::Frecent),
+            "[**/test/item**]"
+        );
+        assert_eq!(render("[{rank:0>3}]", SortMethod::Frecent), "[007]");
+    }
+
+    #[test]
+    fn render_escapes() {
+        assert_eq!(
+            render("{{{score}}}\\t{item}\\0", SortMethod::Frequent),
+            "{3}\t/test/item\0"
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(Template::parse("{unknown}").is_err());
+        assert!(Template::parse("{item").is_err());
+        assert!(Template::parse("item}").is_err());
+        assert!(Template::parse("{rank:x3}").is_err());
+        assert!(Template::parse("{frecency:.70000}").is_err());
+        assert!(Template::parse("{item:256}").is_err());
+        assert!(Template::parse("{frecency:255.255}").is_ok());
+        assert!(Template::parse("{item:relative}").is_err());
+        assert!(Template::parse("{meta.}").is_err());
+    }
+
+    #[test]
+    fn human_durations() {
+        assert_eq!(human_duration(-5.0), "0s");
+        assert_eq!(human_duration(59.0), "59s");
+        assert_eq!(human_duration(90.0), "1m");
+        assert_eq!(human_duration(60.0 * 60.0 * 24.0 * 10.0), "1w");
+        assert_eq!(human_duration(60.0 * 60.0 * 24.0 * 800.0), "2y");
+    }
+
+    #[test]
+    fn write_with_terminator() {
+        let t = crate::current_time_secs();
+        let items = vec![create_item(t), create_item(t)];
+        let template = Template::parse("{rank}:{percent:.0}").unwrap();
+
+        let mut b = Vec::new();
+        write_template(&mut b, &items, &template, SortMethod::Frecent, t, '\0').unwrap();
+
+        assert_eq!(String::from_utf8(b).unwrap(), "1:50\x002:50\0");
+    }
+}
@@ -0,0 +1,550 @@
+use super::current_time_secs;
+use super::stats::ItemStats;
+use super::store::{write_stat, FrecencyStore};
+use crate::args::SortMethod;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+use std::io::{self, BufWriter, Write};
+
+/// Number of lines above the item list used for the prompt and the status line
+const HEADER_LINES: u16 = 2;
+
+/// The outcome of handling a single key press in the picker
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    /// Keep the picker open
+    Continue,
+    /// Close the picker, choosing the given item
+    Select(String),
+    /// Close the picker without choosing an item
+    Cancel,
+}
+
+/// The state of the interactive picker
+pub struct Picker {
+    sort_method: SortMethod,
+    limit: Option<usize>,
+    items: Vec<ItemStats>,
+    query: String,
+    // Indices into `items` of the items matching `query`
+    matches: Vec<usize>,
+    // Index into `matches` of the highlighted item
+    selected: usize,
+    // Index into `matches` of the first item on screen
+    scroll: usize,
+}
+
+impl Picker {
+    /// Create a picker over the items of `store`, sorted by `sort_method`
+    pub fn new(store: &FrecencyStore, sort_method: SortMethod, limit: Option<usize>) -> Picker {
+        let mut picker = Picker {
+            sort_method,
+            limit,
+            items: Vec::new(),
+            query: String::new(),
+            matches: Vec::new(),
+            selected: 0,
+            scroll: 0,
+        };
+        picker.reload(store);
+        picker
+    }
+
+    /// The currently highlighted item, if any item matches the query
+    pub fn selected_item(&self) -> Option<&ItemStats> {
+        self.matches.get(self.selected).map(|&idx| &self.items[idx])
+    }
+
+    /// The items matching the current query, in display order
+    pub fn matches(&self) -> impl Iterator<Item = &ItemStats> {
+        self.matches.iter().map(|&idx| &self.items[idx])
+    }
+
+    /// Update the picker state for a key press, modifying `store` for the
+    /// delete, pin and bump bindings
+    pub fn handle_key(&mut self, store: &mut FrecencyStore, key: KeyEvent) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('c') | KeyCode::Char('g') if ctrl => return Action::Cancel,
+            KeyCode::Enter => {
+                if let Some(item) = self.selected_item() {
+                    return Action::Select(item.item.clone());
+                }
+            }
+            KeyCode::Up => self.move_selection(-1),
+            KeyCode::Char('p') | KeyCode::Char('k') if ctrl => self.move_selection(-1),
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Char('n') | KeyCode::Char('j') if ctrl => self.move_selection(1),
+            KeyCode::Char('d') if ctrl => {
+                if let Some(item) = self.selected_item().map(|i| i.item.clone()) {
+                    store.delete(&item);
+                    self.reload(store);
+                }
+            }
+            KeyCode::Char('t') if ctrl => {
+                if let Some(item) = self.selected_item().map(|i| i.item.clone()) {
+                    store.toggle_pin(&item);
+                    self.reload(store);
+                    self.select_item(&item);
+                }
+            }
+            KeyCode::Char('b') if ctrl => {
+                if let Some(item) = self.selected_item().map(|i| i.item.clone()) {
+                    // A visit now can only fail to be added if the frecency is out of range,
+                    // in which case the item is left as it is
+                    let _ = store.add(&item);
+                    self.reload(store);
+                    self.select_item(&item);
+                }
+            }
+            KeyCode::Char('u') if ctrl => {
+                self.query.clear();
+                self.filter();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.filter();
+            }
+            KeyCode::Char(c) if !ctrl && !alt => {
+                self.query.push(c);
+                self.filter();
+            }
+            _ => {}
+        }
+
+        Action::Continue
+    }
+
+    /// Draw the picker onto a terminal of the given size
+    pub fn render<W: Write>(
+        &mut self,
+        w: &mut W,
+        width: u16,
+        height: u16,
+        current_time: f64,
+    ) -> Result<()> {
+        let width = width as usize;
+        let list_height = height.saturating_sub(HEADER_LINES) as usize;
+
+        // Keep the selection on screen
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if list_height > 0 && self.selected >= self.scroll + list_height {
+            self.scroll = self.selected + 1 - list_height;
+        }
+
+        let visible: Vec<(usize, String, &ItemStats)> = self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(list_height)
+            .map(|(pos, &idx)| {
+                let item = &self.items[idx];
+                let score = score_column(item, self.sort_method, current_time)?;
+                Ok((pos, score, item))
+            })
+            .collect::<Result<_>>()?;
+        let score_width = visible.iter().map(|(_, s, _)| s.len()).max().unwrap_or(0);
+
+        queue!(w, terminal::Clear(ClearType::All))?;
+
+        let status = format!(
+            "  {}/{}  enter:select ^D:delete ^T:pin ^B:bump esc:quit",
+            self.matches.len(),
+            self.items.len()
+        );
+        queue!(w, cursor::MoveTo(0, 1), Print(truncate(&status, width)),)?;
+
+        for (row, (pos, score, item)) in visible.into_iter().enumerate() {
+            let selected = pos == self.selected;
+            let line = format!(
+                "{}{} {:>score_width$}  {}",
+                if selected { '>' } else { ' ' },
+                if item.pinned { '*' } else { ' ' },
+                score,
+                item.item,
+            );
+            queue!(w, cursor::MoveTo(0, HEADER_LINES + row as u16))?;
+            if selected {
+                queue!(w, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                w,
+                Print(truncate(&line, width)),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+
+        let prompt = format!("> {}", self.query);
+        queue!(
+            w,
+            cursor::MoveTo(0, 0),
+            Print(truncate(&prompt, width)),
+            cursor::Show,
+        )?;
+
+        Ok(())
+    }
+
+    /// Move the highlight by `delta` lines, staying within the matched items
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.matches.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Highlight `item` if it is among the matched items
+    fn select_item(&mut self, item: &str) {
+        let pos = self.matches().position(|i| i.item == item);
+        if let Some(pos) = pos {
+            self.selected = pos;
+        }
+    }
+
+    /// Re-read the sorted items from `store`
+    fn reload(&mut self, store: &FrecencyStore) {
+        let mut items = store.listed(self.sort_method);
+        if let Some(l) = self.limit {
+            items.truncate(l);
+        }
+        self.items = items;
+        self.filter();
+    }
+
+    /// Recompute the items matching the current query
+    fn filter(&mut self) {
+        self.matches = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| fuzzy_match(&self.query, &i.item))
+            .map(|(idx, _)| idx)
+            .collect();
+        self.move_selection(0);
+    }
+}
+
+/// Return whether every whitespace-separated term of `query` matches `item`
+/// as a subsequence. Terms are matched case insensitively unless they
+/// contain an uppercase character.
+pub fn fuzzy_match(query: &str, item: &str) -> bool {
+    query.split_whitespace().all(|term| {
+        let case_sensitive = term.chars().any(char::is_uppercase);
+        let mut haystack = item.chars();
+        term.chars().all(|q| {
+            haystack.any(|c| {
+                if case_sensitive {
+                    c == q
+                } else {
+                    c.to_lowercase().eq(q.to_lowercase())
+                }
+            })
+        })
+    })
+}
+
+/// Return the item with the alias `query`, or else the highest scoring item whose name or
+/// label fuzzy matches `query`
+pub fn best(store: &FrecencyStore, query: &str, sort_method: SortMethod) -> Option<String> {
+    if let Some(item) = store.find_alias(query) {
+        return Some(item.item.clone());
+    }
+    store
+        .sorted(sort_method)
+        .into_iter()
+        .find(|i| {
+            fuzzy_match(query, &i.item)
+                || i.label
+                    .as_deref()
+                    .is_some_and(|label| fuzzy_match(query, label))
+        })
+        .map(|i| i.item)
+}
+
+/// Run the picker on the terminal until an item is chosen or the picker is
+/// closed. The picker is drawn on stderr so the chosen item can be captured
+/// from stdout.
+pub fn run(
+    store: &mut FrecencyStore,
+    sort_method: SortMethod,
+    limit: Option<usize>,
+) -> Result<Option<String>> {
+    let mut picker = Picker::new(store, sort_method, limit);
+    let _terminal = RawTerminal::enter()?;
+    let mut w = BufWriter::new(io::stderr().lock());
+
+    loop {
+        let (width, height) = terminal::size()?;
+        picker.render(&mut w, width, height, current_time_secs())?;
+        w.flush()?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match picker.handle_key(store, key) {
+                Action::Continue => {}
+                Action::Select(item) => return Ok(Some(item)),
+                Action::Cancel => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Puts the terminal into raw mode on an alternate screen, restoring it when dropped
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> Result<RawTerminal> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stderr(), terminal::EnterAlternateScreen)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stderr(), terminal::LeaveAlternateScreen, cursor::Show);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Format the score of an item the same way as `--stat`
+fn score_column(item: &ItemStats, method: SortMethod, current_time: f64) -> Result<String> {
+    let mut b = Vec::new();
+    write_stat(&mut b, item, method, true, current_time, None, '\n')?;
+    let line = String::from_utf8_lossy(&b);
+    let (score, _) = line.split_once('\t').unwrap_or_default();
+    Ok(score.to_string())
+}
+
+/// Cut `line` down to at most `width` characters
+fn truncate(line: &str, width: usize) -> String {
+    line.chars().take(width).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_store() -> FrecencyStore {
+        let mut store = FrecencyStore::default();
+        store.add("/home/user/src").unwrap();
+        store.add("/home/user/src").unwrap();
+        store.add("/home/user/src").unwrap();
+        store.add("/home/user/docs").unwrap();
+        store.add("/home/user/docs").unwrap();
+        store.add("/etc").unwrap();
+        store
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn matched_items(picker: &Picker) -> Vec<&str> {
+        picker.matches().map(|i| i.item.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_match_subsequence() {
+        assert!(fuzzy_match("", "/home/user"));
+        assert!(fuzzy_match("hmusr", "/home/user"));
+        assert!(fuzzy_match("HOME", "/HOME/user"));
+        assert!(fuzzy_match("user home", "/home/user"));
+        assert!(!fuzzy_match("usrhm", "/home/user"));
+        assert!(!fuzzy_match("HOME", "/home/user"));
+    }
+
+    #[test]
+    fn best_prefers_alias() {
+        let mut store = create_store();
+        store.item_mut("/etc").unwrap().alias = Some("docs".to_string());
+        store.item_mut("/home/user/docs").unwrap().label = Some("notes".to_string());
+
+        assert_eq!(best(&store, "docs", SortMethod::Frecent).unwrap(), "/etc");
+        assert_eq!(
+            best(&store, "user", SortMethod::Frecent).unwrap(),
+            "/home/user/src"
+        );
+        assert_eq!(
+            best(&store, "nts", SortMethod::Frecent).unwrap(),
+            "/home/user/docs"
+        );
+        assert_eq!(best(&store, "xyz", SortMethod::Frecent), None);
+    }
+
+    #[test]
+    fn initial_order() {
+        let store = create_store();
+        let picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        assert_eq!(
+            matched_items(&picker),
+            vec!["/home/user/src", "/home/user/docs", "/etc"]
+        );
+        assert_eq!(picker.selected_item().unwrap().item, "/home/user/src");
+    }
+
+    #[test]
+    fn limit_items() {
+        let store = create_store();
+        let picker = Picker::new(&store, SortMethod::Frecent, Some(2));
+
+        assert_eq!(
+            matched_items(&picker),
+            vec!["/home/user/src", "/home/user/docs"]
+        );
+    }
+
+    #[test]
+    fn filter_incrementally() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        picker.handle_key(&mut store, key(KeyCode::Char('d')));
+        assert_eq!(matched_items(&picker), vec!["/home/user/docs"]);
+
+        picker.handle_key(&mut store, key(KeyCode::Backspace));
+        picker.handle_key(&mut store, key(KeyCode::Char('e')));
+        assert_eq!(
+            matched_items(&picker),
+            vec!["/home/user/src", "/home/user/docs", "/etc"]
+        );
+
+        picker.handle_key(&mut store, key(KeyCode::Char('t')));
+        assert_eq!(matched_items(&picker), vec!["/etc"]);
+
+        picker.handle_key(&mut store, ctrl('u'));
+        assert_eq!(matched_items(&picker).len(), 3);
+    }
+
+    #[test]
+    fn move_and_select() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        picker.handle_key(&mut store, key(KeyCode::Up));
+        picker.handle_key(&mut store, key(KeyCode::Down));
+        picker.handle_key(&mut store, ctrl('n'));
+        picker.handle_key(&mut store, ctrl('n'));
+        picker.handle_key(&mut store, ctrl('n'));
+        picker.handle_key(&mut store, ctrl('p'));
+
+        assert_eq!(
+            picker.handle_key(&mut store, key(KeyCode::Enter)),
+            Action::Select("/home/user/docs".to_string())
+        );
+    }
+
+    #[test]
+    fn select_no_matches() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        picker.handle_key(&mut store, key(KeyCode::Char('z')));
+
+        assert_eq!(
+            picker.handle_key(&mut store, key(KeyCode::Enter)),
+            Action::Continue
+        );
+    }
+
+    #[test]
+    fn cancel() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        assert_eq!(
+            picker.handle_key(&mut store, key(KeyCode::Esc)),
+            Action::Cancel
+        );
+        assert_eq!(picker.handle_key(&mut store, ctrl('c')), Action::Cancel);
+    }
+
+    #[test]
+    fn delete_selected() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        picker.handle_key(&mut store, key(KeyCode::Down));
+        picker.handle_key(&mut store, ctrl('d'));
+
+        assert_eq!(store.items.len(), 2);
+        assert_eq!(matched_items(&picker), vec!["/home/user/src", "/etc"]);
+        assert_eq!(picker.selected_item().unwrap().item, "/etc");
+    }
+
+    #[test]
+    fn pin_selected() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        picker.handle_key(&mut store, key(KeyCode::Down));
+        picker.handle_key(&mut store, key(KeyCode::Down));
+        picker.handle_key(&mut store, ctrl('t'));
+
+        assert_eq!(
+            matched_items(&picker),
+            vec!["/etc", "/home/user/src", "/home/user/docs"]
+        );
+        assert_eq!(picker.selected_item().unwrap().item, "/etc");
+        assert!(picker.selected_item().unwrap().pinned);
+    }
+
+    #[test]
+    fn bump_selected() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frecent, None);
+
+        picker.handle_key(&mut store, key(KeyCode::Down));
+        picker.handle_key(&mut store, ctrl('b'));
+        picker.handle_key(&mut store, ctrl('b'));
+
+        assert_eq!(
+            matched_items(&picker),
+            vec!["/home/user/docs", "/home/user/src", "/etc"]
+        );
+        assert_eq!(picker.selected_item().unwrap().item, "/home/user/docs");
+    }
+
+    #[test]
+    fn render_items() {
+        let store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frequent, None);
+
+        let mut b = Vec::new();
+        picker.render(&mut b, 80, 24, current_time_secs()).unwrap();
+        let screen = String::from_utf8(b).unwrap();
+
+        assert!(screen.contains("3/3"));
+        assert!(screen.contains(">  3  /home/user/src"));
+        assert!(screen.contains("   2  /home/user/docs"));
+        assert!(screen.contains("   1  /etc"));
+    }
+
+    #[test]
+    fn render_scrolls_to_selection() {
+        let mut store = create_store();
+        let mut picker = Picker::new(&store, SortMethod::Frequent, None);
+        picker.handle_key(&mut store, key(KeyCode::Down));
+        picker.handle_key(&mut store, key(KeyCode::Down));
+
+        let mut b = Vec::new();
+        picker.render(&mut b, 80, 3, current_time_secs()).unwrap();
+        let screen = String::from_utf8(b).unwrap();
+
+        assert!(screen.contains("/etc"));
+        assert!(!screen.contains("/home/user/src"));
+    }
+}
@@ -0,0 +1,86 @@
+use clap::ValueEnum;
+use std::path::Path;
+
+/// What an item refers to, which decides how it is normalized and whether it can go missing
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// A directory
+    Dir,
+    /// A file
+    File,
+    /// A URL, like https://github.com
+    Url,
+    /// A shell command
+    Command,
+    /// Anything else, like the name of a password store entry
+    Other,
+}
+
+impl Kind {
+    /// Guess the kind of `item`. Commands are never guessed, and absolute paths that do not
+    /// exist could be either a directory or a file, so they have no kind
+    pub fn infer(item: &str) -> Option<Kind> {
+        let path = Path::new(item);
+        if is_url(item) {
+            Some(Kind::Url)
+        } else if path.is_dir() {
+            Some(Kind::Dir)
+        } else if path.is_file() {
+            Some(Kind::File)
+        } else if path.is_absolute() {
+            None
+        } else {
+            Some(Kind::Other)
+        }
+    }
+
+    /// Whether items of this kind are paths on the filesystem
+    pub fn is_path(self) -> bool {
+        matches!(self, Kind::Dir | Kind::File)
+    }
+
+    /// The name of the kind, as given on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Dir => "dir",
+            Kind::File => "file",
+            Kind::Url => "url",
+            Kind::Command => "command",
+            Kind::Other => "other",
+        }
+    }
+}
+
+/// Whether `item` starts with a URL scheme followed by `://`
+fn is_url(item: &str) -> bool {
+    match item.split_once("://") {
+        Some((scheme, _)) => {
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infer_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+
+        assert_eq!(Kind::infer("https://github.com"), Some(Kind::Url));
+        assert_eq!(Kind::infer("git+ssh://host/repo"), Some(Kind::Url));
+        assert_eq!(Kind::infer(dir.path().to_str().unwrap()), Some(Kind::Dir));
+        assert_eq!(Kind::infer(file.to_str().unwrap()), Some(Kind::File));
+        assert_eq!(Kind::infer("/nonexistent/path"), None);
+        assert_eq!(Kind::infer("finance/bank"), Some(Kind::Other));
+        assert_eq!(Kind::infer("://nothing"), Some(Kind::Other));
+    }
+}
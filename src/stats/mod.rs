@@ -1,9 +1,14 @@
 use super::current_time_secs;
-use crate::args::SortMethod;
+use crate::args::{Show, SortMethod};
+use anyhow::{bail, Result};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 
+mod kind;
 pub mod serialize;
 
+pub use kind::Kind;
+
 /// A representation of statistics for a single item
 #[derive(Clone)]
 pub struct ItemStats {
@@ -15,6 +20,17 @@ pub struct ItemStats {
     last_accessed: f64,
     frecency: f64,
     pub num_accesses: i32,
+    // Pinned items are always sorted before unpinned items
+    pub pinned: bool,
+    pub tags: BTreeSet<String>,
+    // Arbitrary key/value pairs, like project=fre
+    pub metadata: BTreeMap<String, String>,
+    // A short name that is unique within the store, resolved exactly by --best
+    pub alias: Option<String>,
+    // A name to print in place of the item with --show
+    pub label: Option<String>,
+    // What the item refers to, or `None` if it is not known
+    pub kind: Option<Kind>,
 }
 
 impl ItemStats {
@@ -27,6 +43,12 @@ impl ItemStats {
             frecency: 0.0,
             last_accessed: 0.0,
             num_accesses: 0,
+            pinned: false,
+            tags: BTreeSet::new(),
+            metadata: BTreeMap::new(),
+            alias: None,
+            label: None,
+            kind: None,
         }
     }
 
@@ -70,6 +92,31 @@ impl ItemStats {
         self.frecency / 2.0f64.powf((current_time_secs - self.reference_time) / self.half_life)
     }
 
+    /// The frecency as stored, before it is decayed from the reference time
+    pub fn stored_frecency(&self) -> f64 {
+        self.frecency
+    }
+
+    /// Time in seconds since the epoch that the stored frecency is relative to
+    pub fn reference_time(&self) -> f64 {
+        self.reference_time
+    }
+
+    /// Return the half life in seconds
+    pub fn half_life(&self) -> f64 {
+        self.half_life
+    }
+
+    /// Seconds after `current_time_secs` until the frecency decays below `score`,
+    /// or `None` if it is already below it
+    pub fn secs_until_below(&self, score: f64, current_time_secs: f64) -> Option<f64> {
+        let frecency = self.get_frecency(current_time_secs);
+        if score <= 0.0 || frecency < score {
+            return None;
+        }
+        Some(self.half_life * (frecency / score).log2())
+    }
+
     pub fn set_frecency(&mut self, new: f64) {
         self.frecency =
             new * 2.0f64.powf((current_time_secs() - self.reference_time) / self.half_life);
@@ -81,9 +128,22 @@ impl ItemStats {
         self.set_frecency(original_frecency + weight);
     }
 
+    /// Update the frecency of the item by a weight added at `time` seconds since the epoch,
+    /// so that the weight has already decayed if `time` is in the past. Fails without
+    /// changing the item if the frecency would no longer be a finite number.
+    pub fn update_frecency_at(&mut self, weight: f64, time: f64) -> Result<()> {
+        let frecency =
+            self.frecency + weight * 2.0f64.powf((time - self.reference_time) / self.half_life);
+        if !frecency.is_finite() {
+            bail!("the frecency of {} would be out of range", self.item);
+        }
+        self.frecency = frecency;
+        Ok(())
+    }
+
     /// Update the number of accesses of the item by the given weight
     pub fn update_num_accesses(&mut self, weight: i32) {
-        self.num_accesses += weight;
+        self.num_accesses = self.num_accesses.saturating_add(weight);
     }
 
     /// Update the time the item was last accessed
@@ -91,6 +151,16 @@ impl ItemStats {
         self.last_accessed = time - self.reference_time;
     }
 
+    /// Add the frecency and accesses of `other` to this item, keeping the latest access time
+    pub fn merge(&mut self, other: &ItemStats) {
+        let current_time = current_time_secs();
+        self.set_frecency(self.get_frecency(current_time) + other.get_frecency(current_time));
+        self.update_num_accesses(other.num_accesses);
+        if other.last_access() > self.last_access() {
+            self.update_last_access(other.last_access());
+        }
+    }
+
     /// Reset the reference time and recalculate the last_accessed time
     pub fn reset_ref_time(&mut self, new_time: f64) {
         let original_frecency = self.get_frecency(current_time_secs());
@@ -104,6 +174,22 @@ impl ItemStats {
     pub fn last_access(&self) -> f64 {
         self.reference_time + self.last_accessed
     }
+
+    /// The kind of the item, guessed from the item itself if it was never set
+    pub fn kind_or_infer(&self) -> Option<Kind> {
+        self.kind.or_else(|| Kind::infer(&self.item))
+    }
+
+    /// The name to print for the item: the item, its label, or the label and the item
+    /// separated by a tab. Items without a label are printed in place of the label
+    pub fn display_name(&self, show: Show) -> String {
+        let label = self.label.as_deref().unwrap_or(&self.item);
+        match show {
+            Show::Item => self.item.clone(),
+            Show::Label => label.to_string(),
+            Show::Both => format!("{}\t{}", label, self.item),
+        }
+    }
 }
 
 /// The number of seconds elapsed since `ref_time`
@@ -127,6 +213,12 @@ mod tests {
             frecency: 0.0,
             last_accessed: 0.0,
             num_accesses: 0,
+            pinned: false,
+            tags: BTreeSet::new(),
+            metadata: BTreeMap::new(),
+            alias: None,
+            label: None,
+            kind: None,
         }
     }
 
@@ -192,6 +284,31 @@ mod tests {
         assert_eq!(stats.num_accesses, 0);
     }
 
+    #[test]
+    fn update_score_in_past() {
+        let mut stats = create_item();
+        let t = current_time_secs();
+
+        stats.update_frecency_at(1.0, t - stats.half_life).unwrap();
+        stats.update_frecency_at(1.0, t).unwrap();
+
+        assert!((stats.get_frecency(t) - 1.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn update_score_out_of_range() {
+        let mut stats = create_item();
+        let t = current_time_secs();
+
+        stats.update_frecency_at(1.0, t).unwrap();
+        assert!(stats.update_frecency_at(1.0, t + 1e20).is_err());
+        assert!(stats
+            .update_frecency_at(f64::MAX, t + stats.half_life)
+            .is_err());
+
+        assert!((stats.get_frecency(t) - 1.0).abs() < 0.01);
+    }
+
     #[test]
     fn update_num_accesses() {
         let mut stats = create_item();
@@ -213,7 +330,7 @@ mod tests {
             SortMethod::Recent,
         ] {
             let mut b = Vec::new();
-            write_stat(&mut b, &stats, method, false, t, None).unwrap();
+            write_stat(&mut b, &stats, method, false, t, None, '\n').unwrap();
             assert_eq!(b, String::from("/test/item\n").into_bytes());
         }
     }
@@ -229,11 +346,21 @@ mod tests {
             (SortMethod::Frequent, String::from("0\t/test/item\n")),
         ] {
             let mut b = Vec::new();
-            write_stat(&mut b, &stats, method, true, t, None).unwrap();
+            write_stat(&mut b, &stats, method, true, t, None, '\n').unwrap();
             assert_eq!(String::from_utf8(b).unwrap(), expected);
         }
     }
 
+    #[test]
+    fn to_string_null_terminated() {
+        let stats = create_item();
+
+        let t = current_time_secs();
+        let mut b = Vec::new();
+        write_stat(&mut b, &stats, SortMethod::Frequent, true, t, None, '\0').unwrap();
+        assert_eq!(String::from_utf8(b).unwrap(), "0\t/test/item\0");
+    }
+
     #[test]
     fn to_string_custom_precision() {
         let t = current_time_secs();
@@ -249,11 +376,55 @@ mod tests {
             (SortMethod::Frequent, String::from("15.00000\t/test/item\n")),
         ] {
             let mut b = Vec::new();
-            write_stat(&mut b, &stats, method, true, t, Some(5)).unwrap();
+            write_stat(&mut b, &stats, method, true, t, Some(5), '\n').unwrap();
             assert_eq!(String::from_utf8(b).unwrap(), expected);
         }
     }
 
+    #[test]
+    fn display_name_with_label() {
+        let mut item = create_item();
+        assert_eq!(item.display_name(Show::Label), "/test/item");
+        assert_eq!(item.display_name(Show::Both), "/test/item\t/test/item");
+
+        item.label = Some("test".to_string());
+        assert_eq!(item.display_name(Show::Item), "/test/item");
+        assert_eq!(item.display_name(Show::Label), "test");
+        assert_eq!(item.display_name(Show::Both), "test\t/test/item");
+    }
+
+    #[test]
+    fn decay_below_score() {
+        let mut item = create_item();
+        let t = item.reference_time;
+        item.frecency = 4.0;
+
+        assert!((item.secs_until_below(1.0, t).unwrap() - 200.0).abs() < 0.01);
+        assert!((item.secs_until_below(2.0, t + 50.0).unwrap() - 50.0).abs() < 0.01);
+        assert_eq!(item.secs_until_below(5.0, t), None);
+        assert_eq!(item.secs_until_below(0.0, t), None);
+    }
+
+    #[test]
+    fn merge_items() {
+        let t = current_time_secs();
+        let mut stats = create_item();
+        stats.update_frecency(1.0);
+        stats.update_num_accesses(1);
+        stats.update_last_access(t - 10.0);
+        let mut other = create_item();
+        other.reset_ref_time(t - 50.0);
+        other.update_frecency(2.0);
+        other.update_num_accesses(2);
+        other.update_last_access(t);
+
+        stats.merge(&other);
+
+        assert!((stats.get_frecency(t) - 3.0).abs() < 0.01);
+        assert_eq!(stats.num_accesses, 3);
+        assert!((stats.last_access() - t).abs() < 0.01);
+    }
+
     #[test]
     fn get_frecency_one_half_life() {
         let mut stats = create_item();
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ItemStatsSerializer {
@@ -6,6 +7,18 @@ pub struct ItemStatsSerializer {
     pub frecency: f64,
     pub last_accessed: f64,
     pub num_accesses: i32,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<Kind>,
 }
 
 impl From<ItemStats> for ItemStatsSerializer {
@@ -15,6 +28,12 @@ impl From<ItemStats> for ItemStatsSerializer {
             frecency: stats.frecency,
             last_accessed: stats.last_accessed,
             num_accesses: stats.num_accesses,
+            pinned: stats.pinned,
+            tags: stats.tags,
+            metadata: stats.metadata,
+            alias: stats.alias,
+            label: stats.label,
+            kind: stats.kind,
         }
     }
 }
@@ -28,6 +47,12 @@ impl ItemStatsSerializer {
             frecency: self.frecency,
             last_accessed: self.last_accessed,
             num_accesses: self.num_accesses,
+            pinned: self.pinned,
+            tags: self.tags,
+            metadata: self.metadata,
+            alias: self.alias,
+            label: self.label,
+            kind: self.kind,
         }
     }
 }
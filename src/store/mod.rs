@@ -1,38 +1,50 @@
+mod namespaces;
 mod serialize;
 
+pub use namespaces::{Namespaces, DEFAULT_NAMESPACE};
+
 use super::current_time_secs;
-use super::stats::ItemStats;
+use super::stats::{ItemStats, Kind};
 use crate::args::SortMethod;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::default::Default;
 use std::fs::{self, File};
 use std::io::{self, BufReader, BufWriter, Write};
 use std::path::PathBuf;
+use tempfile::NamedTempFile;
 
-/// Parses the file at `path` into a `UsageStore` object
-pub fn read_store(path: &PathBuf) -> Result<FrecencyStore, io::Error> {
+/// Parses the file at `path` into the namespaces it contains
+pub fn read_store(path: &PathBuf) -> Result<Namespaces, io::Error> {
     if path.is_file() {
         let file = File::open(path)?;
         let reader = BufReader::new(file);
-        let store: serialize::FrecencyStoreSerializer = serde_json::from_reader(reader)?;
-        Ok(FrecencyStore::from(store))
+        let store: serialize::NamespacesSerializer = serde_json::from_reader(reader)?;
+        Ok(Namespaces::from(store))
     } else {
-        Ok(FrecencyStore::default())
+        Ok(Namespaces::default())
     }
 }
 
-/// Serializes and writes a `UsageStore` to a file
-pub fn write_store(store: FrecencyStore, path: &PathBuf) -> io::Result<()> {
+/// Serializes and writes the namespaces of a store to a file.
+/// The store is written to a temporary file in the same directory and renamed over
+/// `path`, so concurrent readers and writers never see a partially written store.
+pub fn write_store(namespaces: Namespaces, path: &PathBuf) -> io::Result<()> {
     let store_dir = path.parent().expect("file must have parent");
     fs::create_dir_all(store_dir)?;
-    let file = File::create(path)?;
-    let writer = BufWriter::new(file);
-    serde_json::to_writer_pretty(writer, &serialize::FrecencyStoreSerializer::from(store))?;
+    let file = NamedTempFile::new_in(store_dir)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(
+        &mut writer,
+        &serialize::NamespacesSerializer::from(namespaces),
+    )?;
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.persist(path).map_err(|e| e.error)?;
 
     Ok(())
 }
 
 /// A collection of statistics about the stored items
+#[derive(Clone)]
 pub struct FrecencyStore {
     reference_time: f64,
     half_life: f64,
@@ -54,6 +66,8 @@ impl FrecencyStore {
     pub fn truncate(&mut self, keep_num: usize, sort_method: SortMethod) {
         let mut sorted_vec = self.sorted(sort_method);
         sorted_vec.truncate(keep_num);
+        // Items are kept sorted by name so they can be found with a binary search
+        sorted_vec.sort_by(|item1, item2| item1.item.cmp(&item2.item));
         self.items = sorted_vec;
     }
 
@@ -67,6 +81,11 @@ impl FrecencyStore {
         }
     }
 
+    /// Return the half life in seconds
+    pub fn half_life(&self) -> f64 {
+        self.half_life
+    }
+
     /// Return the number of half lives passed since the reference time
     pub fn half_lives_passed(&self) -> f64 {
         (current_time_secs() - self.reference_time) / self.half_life
@@ -84,20 +103,51 @@ impl FrecencyStore {
     }
 
     /// Log a visit to a item
-    pub fn add(&mut self, item: &str) {
-        let item_stats = self.get(item);
+    pub fn add(&mut self, item: &str) -> Result<()> {
+        self.add_at(item, current_time_secs())
+    }
 
-        item_stats.update_frecency(1.0);
-        item_stats.update_num_accesses(1);
-        item_stats.update_last_access(current_time_secs());
+    /// Log a visit to an item at `time` seconds since the epoch, which may be in the past.
+    /// The last access time of an existing item is only moved forward.
+    pub fn add_at(&mut self, item: &str, time: f64) -> Result<()> {
+        self.record(item, 1.0, 1, time)
     }
 
-    /// Adjust the score of a item by a given weight
-    pub fn adjust(&mut self, item: &str, weight: f64) {
+    /// Log `num_accesses` visits to an item with a total weight of `weight`, all at `time`
+    /// seconds since the epoch. The last access time of an existing item is only moved forward.
+    pub fn record(&mut self, item: &str, weight: f64, num_accesses: i32, time: f64) -> Result<()> {
+        let is_new = self.find(item).is_err();
+        self.update_frecency_at(item, weight, time)?;
+
         let item_stats = self.get(item);
+        item_stats.update_num_accesses(num_accesses);
+        if is_new || time > item_stats.last_access() {
+            item_stats.update_last_access(time);
+        }
+        Ok(())
+    }
+
+    /// Adjust the score of a item by a given weight
+    pub fn adjust(&mut self, item: &str, weight: f64) -> Result<()> {
+        self.adjust_at(item, weight, current_time_secs())
+    }
 
-        item_stats.update_frecency(weight);
-        item_stats.update_num_accesses(weight as i32);
+    /// Adjust the score of an item by a given weight as of `time` seconds since the epoch
+    pub fn adjust_at(&mut self, item: &str, weight: f64, time: f64) -> Result<()> {
+        self.update_frecency_at(item, weight, time)?;
+        self.get(item).update_num_accesses(weight as i32);
+        Ok(())
+    }
+
+    /// Update the frecency of an item, adding it if it is new. A new item is removed
+    /// again if the update fails, so that a failed update leaves the store unchanged.
+    fn update_frecency_at(&mut self, item: &str, weight: f64, time: f64) -> Result<()> {
+        let is_new = self.find(item).is_err();
+        let result = self.get(item).update_frecency_at(weight, time);
+        if result.is_err() && is_new {
+            self.delete(item);
+        }
+        result
     }
 
     /// Delete an item from the store
@@ -107,10 +157,76 @@ impl FrecencyStore {
         }
     }
 
+    /// Remove every item for which `keep` returns false
+    pub fn retain<F: FnMut(&ItemStats) -> bool>(&mut self, keep: F) {
+        self.items.retain(keep);
+    }
+
+    /// Whether the store has an item
+    pub fn contains(&self, item: &str) -> bool {
+        self.find(item).is_ok()
+    }
+
+    /// Retrieve a reference to an item, or `None` if it is not in the store
+    pub fn item(&self, item: &str) -> Option<&ItemStats> {
+        let idx = self.find(item).ok()?;
+        Some(&self.items[idx])
+    }
+
+    /// Retrieve a mutable reference to an item, or `None` if it is not in the store
+    pub fn item_mut(&mut self, item: &str) -> Option<&mut ItemStats> {
+        let idx = self.find(item).ok()?;
+        Some(&mut self.items[idx])
+    }
+
+    /// Return the item with the alias `alias`, if there is one
+    pub fn find_alias(&self, alias: &str) -> Option<&ItemStats> {
+        self.items
+            .iter()
+            .find(|i| i.alias.as_deref() == Some(alias))
+    }
+
+    /// Check that `alias` can be given to `item` without another item having the same alias
+    pub fn check_alias(&self, alias: &str, item: &str) -> Result<()> {
+        match self.find_alias(alias) {
+            Some(other) if other.item != item => {
+                bail!("alias '{}' is already used by {}", alias, other.item)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the kind of an item in the store to `kind`, or guess it if `kind` is `None`
+    /// and the item has no kind yet
+    pub fn set_kind(&mut self, item: &str, kind: Option<Kind>) {
+        if let Some(stats) = self.item_mut(item) {
+            if kind.is_some() || stats.kind.is_none() {
+                stats.kind = kind.or_else(|| Kind::infer(item));
+            }
+        }
+    }
+
+    /// Toggle whether an item is pinned to the top of listings and the picker.
+    /// Returns the new pinned state, or `None` if the item is not in the store
+    pub fn toggle_pin(&mut self, item: &str) -> Option<bool> {
+        let item_stats = self.items.iter_mut().find(|i| i.item == item)?;
+        item_stats.pinned = !item_stats.pinned;
+        Some(item_stats.pinned)
+    }
+
     /// Return a sorted vector of all the items in the store, sorted by `sort_method`
     pub fn sorted(&self, sort_method: SortMethod) -> Vec<ItemStats> {
         let mut new_vec = self.items.clone();
-        new_vec.sort_by(|item1, item2| item1.cmp_score(item2, sort_method).reverse());
+        sort_items(&mut new_vec, sort_method);
+
+        new_vec
+    }
+
+    /// Return the items in the order they are listed and picked from: sorted by
+    /// `sort_method`, with pinned items before unpinned items
+    pub fn listed(&self, sort_method: SortMethod) -> Vec<ItemStats> {
+        let mut new_vec = self.sorted(sort_method);
+        pin_first(&mut new_vec);
 
         new_vec
     }
@@ -118,10 +234,7 @@ impl FrecencyStore {
     /// Retrieve a mutable reference to a item in the store.
     /// If the item does not exist, create it and return a reference to the created item
     fn get(&mut self, item: &str) -> &mut ItemStats {
-        match self
-            .items
-            .binary_search_by_key(&item, |item_stats| &item_stats.item)
-        {
+        match self.find(item) {
             Ok(idx) => &mut self.items[idx],
             Err(idx) => {
                 self.items.insert(
@@ -132,9 +245,25 @@ impl FrecencyStore {
             }
         }
     }
+
+    /// Binary search for an item, returning its index or the index it would be inserted at
+    fn find(&self, item: &str) -> Result<usize, usize> {
+        self.items
+            .binary_search_by_key(&item, |item_stats| &item_stats.item)
+    }
+}
+
+/// Sort items from highest to lowest score by `sort_method`
+pub fn sort_items(items: &mut [ItemStats], sort_method: SortMethod) {
+    items.sort_by(|item1, item2| item1.cmp_score(item2, sort_method).reverse());
 }
 
-/// Print out all the items, sorted by `method`, with an optional maximum of `limit`
+/// Move pinned items before unpinned items, keeping the order of each
+pub fn pin_first(items: &mut [ItemStats]) {
+    items.sort_by_key(|item| !item.pinned);
+}
+
+/// Print out all the items, sorted by `method`, each followed by `terminator`
 pub fn write_stats<W: Write>(
     w: &mut W,
     items: &[ItemStats],
@@ -142,6 +271,7 @@ pub fn write_stats<W: Write>(
     show_stats: bool,
     current_time: f64,
     precision_override: Option<usize>,
+    terminator: char,
 ) -> Result<()> {
     for item in items {
         write_stat(
@@ -151,6 +281,7 @@ pub fn write_stats<W: Write>(
             show_stats,
             current_time,
             precision_override,
+            terminator,
         )?;
     }
     Ok(())
@@ -163,6 +294,7 @@ pub fn write_stat<W: Write>(
     show_stats: bool,
     current_time: f64,
     precision_override: Option<usize>,
+    terminator: char,
 ) -> Result<()> {
     if show_stats {
         let (score, default_precision) = match method {
@@ -172,13 +304,14 @@ pub fn write_stat<W: Write>(
         };
         let precision = precision_override.unwrap_or(default_precision);
         w.write_fmt(format_args!(
-            "{: <.prec$}\t{}\n",
+            "{: <.prec$}\t{}{}",
             score,
             item.item,
+            terminator,
             prec = precision
         ))?;
     } else {
-        w.write_fmt(format_args!("{}\n", &item.item))?;
+        w.write_fmt(format_args!("{}{}", &item.item, terminator))?;
     }
     Ok(())
 }
@@ -199,7 +332,7 @@ mod tests {
     fn add_new() {
         let mut usage = create_usage();
 
-        usage.add("test");
+        usage.add("test").unwrap();
 
         assert_eq!(1, usage.items.len());
     }
@@ -208,16 +341,78 @@ mod tests {
     fn add_existing() {
         let mut usage = create_usage();
 
-        usage.add("test");
-        usage.add("test");
+        usage.add("test").unwrap();
+        usage.add("test").unwrap();
 
         assert_eq!(1, usage.items.len());
     }
 
+    #[test]
+    fn add_at_past() {
+        let mut usage = create_usage();
+        let t = current_time_secs();
+
+        usage.add_at("test", t - 1.0).unwrap();
+        usage.add_at("test", t - 10.0).unwrap();
+
+        let stats = usage.get("test");
+        assert_eq!(stats.num_accesses, 2);
+        assert!((stats.last_access() - (t - 1.0)).abs() < 0.01);
+        assert!((stats.get_frecency(t) - (0.5 + 0.5f64.powf(10.0))).abs() < 0.01);
+    }
+
+    #[test]
+    fn add_at_new_in_past() {
+        let mut usage = create_usage();
+        let t = current_time_secs();
+
+        usage.add_at("test", t - 100.0).unwrap();
+
+        assert!((usage.get("test").last_access() - (t - 100.0)).abs() < 0.01);
+    }
+
+    #[test]
+    fn record_many() {
+        let mut usage = create_usage();
+        let t = current_time_secs();
+
+        usage.record("test", 8.0, 5, t - 3.0).unwrap();
+
+        let stats = usage.get("test");
+        assert_eq!(stats.num_accesses, 5);
+        assert!((stats.last_access() - (t - 3.0)).abs() < 0.01);
+        assert!((stats.get_frecency(t) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn record_out_of_range() {
+        let mut usage = create_usage();
+        let t = current_time_secs();
+        usage.add_at("test", t).unwrap();
+
+        assert!(usage.record("new", 1.0, 1, t + 1e20).is_err());
+        assert!(usage.adjust_at("test", f64::MAX, t + 1e20).is_err());
+
+        assert!(!usage.contains("new"));
+        assert_eq!(usage.get("test").num_accesses, 1);
+    }
+
+    #[test]
+    fn adjust_at_past() {
+        let mut usage = create_usage();
+        let t = current_time_secs();
+
+        usage.adjust_at("test", 4.0, t - 2.0).unwrap();
+
+        let stats = usage.get("test");
+        assert_eq!(stats.num_accesses, 4);
+        assert!((stats.get_frecency(t) - 1.0).abs() < 0.05);
+    }
+
     #[test]
     fn delete_existing() {
         let mut usage = create_usage();
-        usage.add("test");
+        usage.add("test").unwrap();
         assert_eq!(usage.items.len(), 1);
         usage.delete("test");
         assert_eq!(usage.items.len(), 0);
@@ -234,8 +429,8 @@ mod tests {
     fn adjust_existing() {
         let mut usage = create_usage();
 
-        usage.add("test");
-        usage.adjust("test", 3.0);
+        usage.add("test").unwrap();
+        usage.adjust("test", 3.0).unwrap();
 
         assert_eq!(usage.items.len(), 1);
     }
@@ -244,7 +439,7 @@ mod tests {
     fn adjust_new() {
         let mut usage = create_usage();
 
-        usage.adjust("test", 3.0);
+        usage.adjust("test", 3.0).unwrap();
 
         assert_eq!(usage.items.len(), 1);
     }
@@ -252,19 +447,33 @@ mod tests {
     #[test]
     fn truncate_greater() {
         let mut usage = create_usage();
-        usage.add("dir1");
-        usage.add("dir2");
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
 
         usage.truncate(1, SortMethod::Recent);
 
         assert_eq!(usage.items.len(), 1);
     }
 
+    #[test]
+    fn truncate_then_add() {
+        let mut usage = create_usage();
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
+        usage.get("dir1").update_frecency(1000.0);
+
+        usage.truncate(2, SortMethod::Frecent);
+        usage.add("dir2").unwrap();
+
+        assert_eq!(usage.items.len(), 2);
+        assert_eq!(usage.get("dir2").num_accesses, 2);
+    }
+
     #[test]
     fn truncate_less() {
         let mut usage = create_usage();
-        usage.add("dir1");
-        usage.add("dir2");
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
 
         usage.truncate(3, SortMethod::Recent);
 
@@ -274,8 +483,8 @@ mod tests {
     #[test]
     fn sorted_frecent() {
         let mut usage = create_usage();
-        usage.add("dir1");
-        usage.add("dir2");
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
         usage.get("dir2").update_frecency(1000.0);
 
         let sorted = usage.sorted(SortMethod::Frecent);
@@ -287,8 +496,8 @@ mod tests {
     #[test]
     fn sorted_frecent2() {
         let mut usage = create_usage();
-        usage.add("dir1");
-        usage.add("dir2");
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
         usage.get("dir1").update_frecency(1000.0);
 
         let sorted = usage.sorted(SortMethod::Frecent);
@@ -300,8 +509,8 @@ mod tests {
     #[test]
     fn sorted_recent() {
         let mut usage = create_usage();
-        usage.add("dir1");
-        usage.add("dir2");
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
         usage
             .get("dir2")
             .update_last_access(current_time_secs() + 100.0);
@@ -315,8 +524,8 @@ mod tests {
     #[test]
     fn sorted_frequent() {
         let mut usage = create_usage();
-        usage.add("dir1");
-        usage.add("dir2");
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
         usage.get("dir2").update_num_accesses(100);
 
         let sorted = usage.sorted(SortMethod::Frequent);
@@ -325,10 +534,84 @@ mod tests {
         assert_eq!(sorted[0].item, "dir2".to_string());
     }
 
+    #[test]
+    fn sorted_pinned_first() {
+        let mut usage = create_usage();
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
+        usage.get("dir1").update_frecency(1000.0);
+
+        assert_eq!(usage.toggle_pin("dir2"), Some(true));
+        let listed = usage.listed(SortMethod::Frecent);
+        assert_eq!(listed[0].item, "dir2".to_string());
+        // Pinning does not change the order by score
+        let sorted = usage.sorted(SortMethod::Frecent);
+        assert_eq!(sorted[0].item, "dir1".to_string());
+
+        assert_eq!(usage.toggle_pin("dir2"), Some(false));
+        let listed = usage.listed(SortMethod::Frecent);
+        assert_eq!(listed[0].item, "dir1".to_string());
+    }
+
+    #[test]
+    fn truncate_ignores_pins() {
+        let mut usage = create_usage();
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
+        usage.get("dir1").update_frecency(1000.0);
+        usage.toggle_pin("dir2");
+
+        usage.truncate(1, SortMethod::Frecent);
+
+        assert_eq!(usage.items.len(), 1);
+        assert_eq!(usage.items[0].item, "dir1".to_string());
+    }
+
+    #[test]
+    fn toggle_pin_nonexisting() {
+        let mut usage = create_usage();
+
+        assert_eq!(usage.toggle_pin("test"), None);
+        assert_eq!(usage.items.len(), 0);
+    }
+
+    #[test]
+    fn alias_unique() {
+        let mut usage = create_usage();
+        usage.add("dir1").unwrap();
+        usage.add("dir2").unwrap();
+        usage.get("dir1").alias = Some("d".to_string());
+
+        assert_eq!(usage.find_alias("d").unwrap().item, "dir1");
+        assert!(usage.find_alias("dir1").is_none());
+        assert!(usage.check_alias("d", "dir1").is_ok());
+        assert!(usage.check_alias("e", "dir2").is_ok());
+        assert_eq!(
+            usage.check_alias("d", "dir2").unwrap_err().to_string(),
+            "alias 'd' is already used by dir1"
+        );
+    }
+
+    #[test]
+    fn set_kind_keeps_explicit() {
+        let mut usage = create_usage();
+        usage.add("https://github.com").unwrap();
+
+        usage.set_kind("https://github.com", None);
+        assert_eq!(usage.get("https://github.com").kind, Some(Kind::Url));
+
+        usage.set_kind("https://github.com", Some(Kind::Other));
+        usage.set_kind("https://github.com", None);
+        assert_eq!(usage.get("https://github.com").kind, Some(Kind::Other));
+
+        usage.set_kind("missing", Some(Kind::Dir));
+        assert_eq!(usage.items.len(), 1);
+    }
+
     #[test]
     fn get_exists() {
         let mut usage = create_usage();
-        usage.add("dir1");
+        usage.add("dir1").unwrap();
 
         let _stats = usage.get("dir1");
 
@@ -338,7 +621,7 @@ mod tests {
     #[test]
     fn get_not_exists() {
         let mut usage = create_usage();
-        usage.add("dir1");
+        usage.add("dir1").unwrap();
 
         usage.get("dir2");
 
@@ -350,7 +633,7 @@ mod tests {
         let mut usage = create_usage();
         let current_time = current_time_secs();
         usage.reference_time = current_time - 10.0;
-        usage.add("test");
+        usage.add("test").unwrap();
         let original_frecency = usage.get("test").get_frecency(current_time);
 
         usage.reset_time();
@@ -364,7 +647,7 @@ mod tests {
         let mut usage = create_usage();
         let current_time = current_time_secs();
         usage.reference_time = current_time - 10.0;
-        usage.add("dir1");
+        usage.add("dir1").unwrap();
         let original_frecency = usage.get("dir1").get_frecency(current_time);
         usage.set_half_life(10.0);
 
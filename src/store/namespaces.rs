@@ -0,0 +1,132 @@
+use super::FrecencyStore;
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+/// The namespace used when none is selected, stored at the top level of the store file
+pub const DEFAULT_NAMESPACE: &str = "default";
+
+/// The namespaces in a store file, each with its own reference time, half life and items
+pub struct Namespaces {
+    pub(super) stores: BTreeMap<String, FrecencyStore>,
+}
+
+impl Default for Namespaces {
+    fn default() -> Namespaces {
+        let mut stores = BTreeMap::new();
+        stores.insert(DEFAULT_NAMESPACE.to_string(), FrecencyStore::default());
+        Namespaces { stores }
+    }
+}
+
+impl Namespaces {
+    /// Return the names of the namespaces, starting with the default namespace
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.stores.keys().map(String::as_str).collect();
+        names.sort_by_key(|&name| name != DEFAULT_NAMESPACE);
+        names
+    }
+
+    /// Remove the namespace `name` and return it, or `None` if it does not exist
+    pub fn take(&mut self, name: &str) -> Option<FrecencyStore> {
+        self.stores.remove(name)
+    }
+
+    /// Add `store` as the namespace `name`, replacing any namespace of that name
+    pub fn insert(&mut self, name: &str, store: FrecencyStore) {
+        self.stores.insert(name.to_string(), store);
+    }
+
+    /// Copy the namespace `from` to a new namespace `to`
+    pub fn copy(&mut self, from: &str, to: &str) -> Result<()> {
+        let store = self.existing(from)?.clone();
+        self.create(to, store)
+    }
+
+    /// Rename the namespace `from` to `to`
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<()> {
+        if from == DEFAULT_NAMESPACE {
+            bail!("cannot rename the default namespace");
+        }
+        if self.stores.contains_key(to) {
+            bail!("namespace '{}' already exists", to);
+        }
+        match self.stores.remove(from) {
+            Some(store) => self.create(to, store),
+            None => bail!("no namespace '{}'", from),
+        }
+    }
+
+    /// Delete the namespace `name` and all of its items
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        if name == DEFAULT_NAMESPACE {
+            bail!("cannot delete the default namespace");
+        }
+        self.existing(name)?;
+        self.stores.remove(name);
+        Ok(())
+    }
+
+    fn existing(&self, name: &str) -> Result<&FrecencyStore> {
+        match self.stores.get(name) {
+            Some(store) => Ok(store),
+            None => bail!("no namespace '{}'", name),
+        }
+    }
+
+    fn create(&mut self, name: &str, store: FrecencyStore) -> Result<()> {
+        if name.is_empty() {
+            bail!("namespace names cannot be empty");
+        }
+        if self.stores.contains_key(name) {
+            bail!("namespace '{}' already exists", name);
+        }
+        self.insert(name, store);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn namespaces() -> Namespaces {
+        let mut namespaces = Namespaces::default();
+        let mut files = FrecencyStore::default();
+        files.add("/home/user/notes.md").unwrap();
+        namespaces.insert("files", files);
+        namespaces.insert("branches", FrecencyStore::default());
+        namespaces
+    }
+
+    #[test]
+    fn names_start_with_default() {
+        assert_eq!(namespaces().names(), vec!["default", "branches", "files"]);
+    }
+
+    #[test]
+    fn copy_rename_delete() {
+        let mut namespaces = namespaces();
+
+        namespaces.copy("files", "docs").unwrap();
+        namespaces.rename("files", "editor").unwrap();
+        namespaces.delete("branches").unwrap();
+
+        assert_eq!(namespaces.names(), vec!["default", "docs", "editor"]);
+        assert_eq!(namespaces.take("docs").unwrap().items.len(), 1);
+        assert_eq!(namespaces.take("editor").unwrap().items.len(), 1);
+    }
+
+    #[test]
+    fn invalid_changes() {
+        let mut namespaces = namespaces();
+
+        assert!(namespaces.copy("missing", "new").is_err());
+        assert!(namespaces.copy("files", "branches").is_err());
+        assert!(namespaces.copy("files", "").is_err());
+        assert!(namespaces.rename("default", "new").is_err());
+        assert!(namespaces.rename("files", "default").is_err());
+        assert!(namespaces.delete("default").is_err());
+        assert!(namespaces.delete("missing").is_err());
+        assert_eq!(namespaces.names(), vec!["default", "branches", "files"]);
+    }
+}
@@ -1,5 +1,47 @@
 use super::super::stats::serialize;
 use super::*;
+use std::collections::BTreeMap;
+
+/// The store file: the default namespace at the top level, as written before namespaces
+/// existed, and any other namespaces by name
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NamespacesSerializer {
+    #[serde(flatten)]
+    default: FrecencyStoreSerializer,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    namespaces: BTreeMap<String, FrecencyStoreSerializer>,
+}
+
+impl From<Namespaces> for NamespacesSerializer {
+    fn from(mut namespaces: Namespaces) -> Self {
+        let default = namespaces.take(DEFAULT_NAMESPACE).unwrap_or_default();
+
+        NamespacesSerializer {
+            default: FrecencyStoreSerializer::from(default),
+            namespaces: namespaces
+                .stores
+                .into_iter()
+                .map(|(name, store)| (name, FrecencyStoreSerializer::from(store)))
+                .collect(),
+        }
+    }
+}
+
+impl From<NamespacesSerializer> for Namespaces {
+    fn from(file: NamespacesSerializer) -> Self {
+        let mut stores: BTreeMap<String, FrecencyStore> = file
+            .namespaces
+            .into_iter()
+            .map(|(name, store)| (name, FrecencyStore::from(store)))
+            .collect();
+        stores.insert(
+            DEFAULT_NAMESPACE.to_string(),
+            FrecencyStore::from(file.default),
+        );
+
+        Namespaces { stores }
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct FrecencyStoreSerializer {
@@ -28,11 +70,13 @@ impl From<FrecencyStoreSerializer> for FrecencyStore {
     fn from(store: FrecencyStoreSerializer) -> Self {
         let ref_time = store.reference_time;
         let half_life = store.half_life;
-        let items = store
+        let mut items: Vec<ItemStats> = store
             .items
             .into_iter()
             .map(|s| s.into_item_stats(ref_time, half_life))
             .collect();
+        // Items are kept sorted by name so they can be found with a binary search
+        items.sort_by(|item1, item2| item1.item.cmp(&item2.item));
 
         FrecencyStore {
             reference_time: store.reference_time,
@@ -0,0 +1,404 @@
+use super::stats::ItemStats;
+use crate::args::SortMethod;
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Make `dir` absolute and remove any `.` and `..` components, without following
+/// symlinks, so that it can be compared with the items in the store
+pub fn absolute_dir(dir: &Path) -> io::Result<PathBuf> {
+    let mut resolved = PathBuf::new();
+    for component in std::path::absolute(dir)?.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            c => resolved.push(c),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Keep only the items that are paths inside `dir`, excluding `dir` itself.
+/// Paths are compared by component, so `/foo/bar` is not inside `/foo/b`.
+pub fn under(items: Vec<ItemStats>, dir: &Path) -> Vec<ItemStats> {
+    items
+        .into_iter()
+        .filter(|i| is_under(&i.item, dir))
+        .collect()
+}
+
+/// Add the scores of every item inside `dir` to each of its ancestors below `dir`,
+/// returning one entry per item or ancestor. Items keep their own annotations, while
+/// ancestors that are not items themselves only get the scores of the items below them.
+pub fn roll_up(items: &[ItemStats], dir: &Path) -> Vec<ItemStats> {
+    let items: Vec<&ItemStats> = items.iter().filter(|i| is_under(&i.item, dir)).collect();
+    let mut rolled: BTreeMap<&Path, ItemStats> = items
+        .iter()
+        .map(|item| (Path::new(&item.item), (*item).clone()))
+        .collect();
+
+    for item in items {
+        let ancestors = Path::new(&item.item).ancestors().skip(1);
+        for ancestor in ancestors.take_while(|a| *a != dir) {
+            rolled
+                .entry(ancestor)
+                .or_insert_with(|| {
+                    let mut stats = ItemStats::new(
+                        ancestor.to_string_lossy().into_owned(),
+                        item.reference_time(),
+                        item.half_life(),
+                    );
+                    stats.update_last_access(item.last_access());
+                    stats
+                })
+                .merge(item);
+        }
+    }
+
+    rolled.into_values().collect()
+}
+
+/// Rewrite each item inside `dir` as a path relative to `dir`
+pub fn make_relative(items: &mut [ItemStats], dir: &Path) {
+    for item in items.iter_mut() {
+        if let Ok(relative) = Path::new(&item.item).strip_prefix(dir) {
+            item.item = relative.to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// The total statistics of every item at or below a path
+#[derive(Default)]
+pub struct TreeNode {
+    pub frecency: f64,
+    pub num_accesses: i32,
+    // Timestamp in seconds since the epoch of the latest access below this node
+    pub last_access: f64,
+    pub children: BTreeMap<String, TreeNode>,
+}
+
+impl TreeNode {
+    /// Group the items by their path components, summing the decayed frecency and
+    /// accesses of every item into each of its ancestors
+    pub fn build(items: &[ItemStats], current_time: f64) -> TreeNode {
+        let mut root = TreeNode::default();
+
+        for item in items {
+            let mut node = &mut root;
+            node.add(item, current_time);
+            for component in Path::new(&item.item).components() {
+                let name = component.as_os_str().to_string_lossy().into_owned();
+                node = node.children.entry(name).or_default();
+                node.add(item, current_time);
+            }
+        }
+
+        root
+    }
+
+    /// Add the statistics of a single item to this node
+    fn add(&mut self, item: &ItemStats, current_time: f64) {
+        self.frecency += item.get_frecency(current_time);
+        self.num_accesses = self.num_accesses.saturating_add(item.num_accesses);
+        self.last_access = self.last_access.max(item.last_access());
+    }
+
+    /// Compare the score of two nodes given a sort method
+    fn cmp_score(&self, other: &TreeNode, method: SortMethod) -> Ordering {
+        match method {
+            SortMethod::Frequent => self.num_accesses.cmp(&other.num_accesses),
+            SortMethod::Recent => self.last_access.total_cmp(&other.last_access),
+            SortMethod::Frecent => self.frecency.total_cmp(&other.frecency),
+        }
+    }
+}
+
+/// Options limiting which nodes of a tree are printed
+pub struct TreeOptions {
+    pub sort_method: SortMethod,
+    /// Do not print nodes more than this many levels deep
+    pub max_depth: Option<usize>,
+    /// Do not print nodes (or their children) with a lower total frecency than this
+    pub threshold: Option<f64>,
+    /// Print at most this many children of each node
+    pub limit: Option<usize>,
+}
+
+/// Print the descendants of `root` as an indented tree, with each node's total
+/// frecency and number of accesses. Siblings are sorted by `sort_method`.
+pub fn write_tree<W: Write>(w: &mut W, root: &TreeNode, options: &TreeOptions) -> Result<()> {
+    write_children(w, root, options, 0)
+}
+
+fn write_children<W: Write>(
+    w: &mut W,
+    node: &TreeNode,
+    options: &TreeOptions,
+    depth: usize,
+) -> Result<()> {
+    if options.max_depth.is_some_and(|max| depth >= max) {
+        return Ok(());
+    }
+
+    let mut children: Vec<(&String, &TreeNode)> = node
+        .children
+        .iter()
+        .filter(|(_, c)| options.threshold.is_none_or(|t| c.frecency >= t))
+        .collect();
+    children.sort_by(|(_, c1), (_, c2)| c1.cmp_score(c2, options.sort_method).reverse());
+    children.truncate(options.limit.unwrap_or(usize::MAX));
+
+    for (name, child) in children {
+        w.write_fmt(format_args!(
+            "{:.3}\t{}\t{:indent$}{}\n",
+            child.frecency,
+            child.num_accesses,
+            "",
+            name,
+            indent = depth * 2
+        ))?;
+        write_children(w, child, options, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+/// Whether `item` is a path strictly inside `dir`
+fn is_under(item: &str, dir: &Path) -> bool {
+    let path = Path::new(item);
+    path.starts_with(dir) && path.components().ne(dir.components())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::current_time_secs;
+    use crate::stats::Kind;
+
+    fn create_items(items: &[(&str, f64, i32)]) -> Vec<ItemStats> {
+        items
+            .iter()
+            .map(|(item, frecency, accesses)| {
+                let mut stats = ItemStats::new(item.to_string(), current_time_secs(), 100.0);
+                stats.update_frecency(*frecency);
+                stats.update_num_accesses(*accesses);
+                stats
+            })
+            .collect()
+    }
+
+    fn names(items: &[ItemStats]) -> Vec<&str> {
+        items.iter().map(|i| i.item.as_str()).collect()
+    }
+
+    #[test]
+    fn absolute_dir_removes_dots() {
+        assert_eq!(
+            absolute_dir(Path::new("/home/user/./src/../docs/..")).unwrap(),
+            PathBuf::from("/home/user")
+        );
+        assert_eq!(absolute_dir(Path::new("/..")).unwrap(), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn under_matches_components() {
+        let items = create_items(&[
+            ("/home/user", 1.0, 1),
+            ("/home/user/src", 1.0, 1),
+            ("/home/user/src/fre", 1.0, 1),
+            ("/home/username", 1.0, 1),
+            ("relative/path", 1.0, 1),
+        ]);
+
+        let filtered = under(items, Path::new("/home/user/"));
+
+        assert_eq!(
+            names(&filtered),
+            vec!["/home/user/src", "/home/user/src/fre"]
+        );
+    }
+
+    #[test]
+    fn relative_paths() {
+        let mut items = create_items(&[("/home/user/src", 1.0, 1), ("/home/user/src/fre", 1.0, 1)]);
+
+        make_relative(&mut items, Path::new("/home/user"));
+
+        assert_eq!(names(&items), vec!["src", "src/fre"]);
+    }
+
+    fn write_tree_string(items: &[ItemStats], options: &TreeOptions) -> String {
+        let tree = TreeNode::build(items, current_time_secs());
+        let mut b = Vec::new();
+        write_tree(&mut b, &tree, options).unwrap();
+        String::from_utf8(b).unwrap()
+    }
+
+    fn tree_options() -> TreeOptions {
+        TreeOptions {
+            sort_method: SortMethod::Frecent,
+            max_depth: None,
+            threshold: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn build_tree_sums_descendants() {
+        let t = current_time_secs();
+        let items = create_items(&[
+            ("/repo", 1.0, 1),
+            ("/repo/src", 2.0, 2),
+            ("/repo/tests", 4.0, 4),
+        ]);
+
+        let tree = TreeNode::build(&items, t);
+        let repo = &tree.children["/"].children["repo"];
+
+        assert!((tree.frecency - 7.0).abs() < 0.01);
+        assert_eq!(repo.num_accesses, 7);
+        assert_eq!(repo.children.len(), 2);
+        assert_eq!(repo.children["tests"].num_accesses, 4);
+    }
+
+    #[test]
+    fn write_tree_sorted() {
+        let items = create_items(&[
+            ("/repo/src", 2.0, 2),
+            ("/repo/tests", 4.0, 4),
+            ("/other", 1.0, 1),
+        ]);
+
+        let tree = write_tree_string(&items, &tree_options());
+
+        assert_eq!(
+            tree,
+            "7.000\t7\t/\n\
+             6.000\t6\t  repo\n\
+             4.000\t4\t    tests\n\
+             2.000\t2\t    src\n\
+             1.000\t1\t  other\n"
+        );
+    }
+
+    #[test]
+    fn write_tree_limited() {
+        let items = create_items(&[
+            ("/repo/src", 2.0, 2),
+            ("/repo/tests", 4.0, 4),
+            ("/other", 1.0, 1),
+        ]);
+
+        let depth_limited = write_tree_string(
+            &items,
+            &TreeOptions {
+                max_depth: Some(2),
+                ..tree_options()
+            },
+        );
+        let threshold_limited = write_tree_string(
+            &items,
+            &TreeOptions {
+                threshold: Some(3.0),
+                ..tree_options()
+            },
+        );
+        let count_limited = write_tree_string(
+            &items,
+            &TreeOptions {
+                limit: Some(1),
+                ..tree_options()
+            },
+        );
+
+        assert_eq!(
+            depth_limited,
+            "7.000\t7\t/\n6.000\t6\t  repo\n1.000\t1\t  other\n"
+        );
+        assert_eq!(
+            threshold_limited,
+            "7.000\t7\t/\n6.000\t6\t  repo\n4.000\t4\t    tests\n"
+        );
+        assert_eq!(
+            count_limited,
+            "7.000\t7\t/\n6.000\t6\t  repo\n4.000\t4\t    tests\n"
+        );
+    }
+
+    #[test]
+    fn build_saturates_accesses() {
+        let items = create_items(&[("/a", 1.0, i32::MAX), ("/b", 1.0, i32::MAX)]);
+
+        let root = TreeNode::build(&items, current_time_secs());
+
+        assert_eq!(root.num_accesses, i32::MAX);
+    }
+
+    #[test]
+    fn roll_up_into_ancestors() {
+        let t = current_time_secs();
+        let items = create_items(&[
+            ("/repo/src", 1.0, 1),
+            ("/repo/src/stats", 2.0, 2),
+            ("/repo/tests/integration", 4.0, 4),
+            ("/elsewhere", 8.0, 8),
+        ]);
+
+        let rolled = roll_up(&items, Path::new("/repo"));
+        let scores: Vec<(&str, i32, bool)> = rolled
+            .iter()
+            .map(|i| {
+                let expected = i.num_accesses as f64;
+                (
+                    i.item.as_str(),
+                    i.num_accesses,
+                    (i.get_frecency(t) - expected).abs() < 0.01,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            scores,
+            vec![
+                ("/repo/src", 3, true),
+                ("/repo/src/stats", 2, true),
+                ("/repo/tests", 4, true),
+                ("/repo/tests/integration", 4, true),
+            ]
+        );
+    }
+
+    #[test]
+    fn roll_up_keeps_annotations_off_ancestors() {
+        let mut items = create_items(&[("/repo/src/stats", 2.0, 2), ("/repo/src", 1.0, 1)]);
+        let child = &mut items[0];
+        child.alias = Some("st".to_string());
+        child.label = Some("Stats".to_string());
+        child.kind = Some(Kind::Dir);
+        child.pinned = true;
+        child.tags.insert("work".to_string());
+        child.metadata.insert("k".to_string(), "v".to_string());
+
+        let rolled = roll_up(&items, Path::new("/"));
+
+        assert_eq!(
+            names(&rolled),
+            vec!["/repo", "/repo/src", "/repo/src/stats"]
+        );
+        for ancestor in &rolled[..2] {
+            assert_eq!(ancestor.alias, None);
+            assert_eq!(ancestor.label, None);
+            assert_eq!(ancestor.kind, None);
+            assert!(!ancestor.pinned);
+            assert!(ancestor.tags.is_empty());
+            assert!(ancestor.metadata.is_empty());
+        }
+        assert_eq!(rolled[0].num_accesses, 3);
+        assert_eq!(rolled[2].alias.as_deref(), Some("st"));
+        assert!(rolled[2].pinned);
+    }
+}
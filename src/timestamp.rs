@@ -0,0 +1,220 @@
+use crate::current_time_secs;
+use anyhow::{anyhow, bail, Result};
+
+const SECS_PER_DAY: i64 = 60 * 60 * 24;
+
+/// Parse a timestamp given as seconds since the epoch or as an RFC 3339 date and time.
+/// Times in the future are rejected.
+pub fn parse_timestamp(s: &str) -> Result<f64> {
+    let time = s
+        .parse::<f64>()
+        .ok()
+        .filter(|t| t.is_finite())
+        .or_else(|| parse_rfc3339(s))
+        .ok_or_else(|| anyhow!("invalid timestamp '{}'", s))?;
+    if time > current_time_secs() {
+        bail!("timestamp '{}' is in the future", s);
+    }
+    Ok(time)
+}
+
+/// Parse an RFC 3339 date and time like `2023-12-28T09:30:00.5+01:00`
+/// into seconds since the epoch
+fn parse_rfc3339(s: &str) -> Option<f64> {
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let number = |s: &str| -> Option<i64> { is_number(s).then(|| s.parse().ok())? };
+
+    let (date, time) = s.split_once(['T', 't', ' '])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year = number(date_parts.next()?)?;
+    let month = number(date_parts.next()?)?;
+    let day = number(date_parts.next()?)?;
+
+    let (time, offset_secs) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else {
+        let idx = time.rfind(['+', '-'])?;
+        let (time, offset) = time.split_at(idx);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':')?;
+        let (hours, minutes) = (number(hours)?, number(minutes)?);
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        (time, sign * (hours * 3600 + minutes * 60))
+    };
+    let mut time_parts = time.splitn(3, ':');
+    let hour = number(time_parts.next()?)?;
+    let minute = number(time_parts.next()?)?;
+    let second = time_parts.next()?;
+    let (second, fraction) = match second.split_once('.') {
+        Some((second, fraction)) => (number(second)?, is_number(fraction).then_some(fraction)?),
+        None => (number(second)?, "0"),
+    };
+    let fraction: f64 = format!("0.{}", fraction).parse().ok()?;
+
+    if !(0..=9999).contains(&year)
+        || !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let secs =
+        days_from_civil(year, month, day) * SECS_PER_DAY + hour * 3600 + minute * 60 + second
+            - offset_secs;
+    Some(secs as f64 + fraction)
+}
+
+/// Format a timestamp in seconds since the epoch as an RFC 3339 date and time in UTC,
+/// truncated to whole seconds
+pub fn format_rfc3339(secs: f64) -> String {
+    let secs = secs.floor() as i64;
+    let days = secs.div_euclid(SECS_PER_DAY);
+    let secs_of_day = secs.rem_euclid(SECS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60
+    )
+}
+
+/// The number of days in `month` of `year` in the proleptic Gregorian calendar
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Convert a (year, month, day) date in the proleptic Gregorian calendar into a
+/// number of days since 1970-01-01
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    // See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Convert a number of days since 1970-01-01 into a (year, month, day) date in the
+/// proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_epoch() {
+        assert_eq!(parse_timestamp("1703721600").unwrap(), 1703721600.0);
+        assert_eq!(parse_timestamp("1703721600.5").unwrap(), 1703721600.5);
+        assert!(parse_timestamp("yesterday").is_err());
+        assert!(parse_timestamp("inf").is_err());
+    }
+
+    #[test]
+    fn parse_rfc3339_utc() {
+        assert_eq!(
+            parse_timestamp("2023-12-28T00:00:00Z").unwrap(),
+            1703721600.0
+        );
+        assert_eq!(
+            parse_timestamp("2000-02-29 12:34:56z").unwrap(),
+            951827696.0
+        );
+        assert_eq!(parse_timestamp("1969-12-31T23:59:59Z").unwrap(), -1.0);
+    }
+
+    #[test]
+    fn parse_rfc3339_offset_and_fraction() {
+        assert_eq!(
+            parse_timestamp("2023-12-28T01:30:00.25+01:30").unwrap(),
+            1703721600.25
+        );
+        assert_eq!(
+            parse_timestamp("2023-12-27T19:00:00-05:00").unwrap(),
+            1703721600.0
+        );
+    }
+
+    #[test]
+    fn parse_rfc3339_invalid() {
+        assert!(parse_timestamp("2023-12-28").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00").is_err());
+        assert!(parse_timestamp("2023-13-28T00:00:00Z").is_err());
+        assert!(parse_timestamp("2023-12-28T24:00:00Z").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00.xZ").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00+0100").is_err());
+        assert!(parse_timestamp("2023-02-31T00:00:00Z").is_err());
+        assert!(parse_timestamp("2023-02-29T00:00:00Z").is_err());
+        assert!(parse_timestamp("1900-02-29T00:00:00Z").is_err());
+        assert!(parse_timestamp("2023-04-31T00:00:00Z").is_err());
+        assert!(parse_timestamp("9223372036854775807-01-01T00:00:00Z").is_err());
+        assert!(parse_timestamp("10000-01-01T00:00:00Z").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00+9999999999999999:00").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00+25:00").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00+01:99").is_err());
+        assert!(parse_timestamp("2023-12-28T00:00:00.99999999999999999999999Z").is_ok());
+    }
+
+    #[test]
+    fn parse_future() {
+        assert!(parse_timestamp("2100-01-01T00:00:00Z").is_err());
+        assert!(parse_timestamp("4102444800").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        for secs in [0.0, 951827696.0, 1703721600.0, -86400.0 * 1000.0] {
+            assert_eq!(parse_timestamp(&format_rfc3339(secs)).unwrap(), secs);
+        }
+    }
+
+    #[test]
+    fn format_epoch() {
+        assert_eq!(format_rfc3339(0.0), "1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn format_truncates_fraction() {
+        assert_eq!(format_rfc3339(1703721600.9), "2023-12-28T00:00:00Z");
+    }
+
+    #[test]
+    fn format_leap_day() {
+        assert_eq!(format_rfc3339(951827696.0), "2000-02-29T12:34:56Z");
+    }
+
+    #[test]
+    fn format_before_epoch() {
+        assert_eq!(format_rfc3339(-1.0), "1969-12-31T23:59:59Z");
+    }
+}
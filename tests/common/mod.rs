@@ -1,16 +1,43 @@
+use assert_cmd::prelude::*;
 use fre::current_time_secs;
 use predicates::*;
 use std::collections::HashMap;
+use std::env;
 use std::io::Write;
+use std::path::Path;
+use std::process::Command;
 use std::str;
-use tempfile;
+
+/// A command running the fre binary without the user's config file or `FRE_*`
+/// environment variables
+pub fn fre_command() -> Command {
+    let mut command = Command::main_binary().unwrap();
+    isolate(&mut command);
+    command
+}
+
+/// Keep the user's config file and `FRE_*` environment variables from `command`,
+/// so that they cannot change the results of a test
+pub fn isolate(command: &mut Command) -> &mut Command {
+    command.env(
+        "XDG_CONFIG_HOME",
+        Path::new(env!("CARGO_TARGET_TMPDIR")).join("no-config"),
+    );
+    for (name, _) in env::vars_os() {
+        if name.to_string_lossy().starts_with("FRE_") {
+            command.env_remove(name);
+        }
+    }
+    command
+}
 
 pub fn get_tempfile_path() -> tempfile::TempPath {
     let mut file = tempfile::NamedTempFile::new().unwrap();
 
-    let current_time = current_time_secs();
+    // A second in the past, so that no item was last accessed in the future
+    let current_time = current_time_secs() - 1.0;
 
-    file.write(
+    file.write_all(
         format!(
             r#"{{
       "reference_time": {},
@@ -42,7 +69,7 @@ pub fn get_tempfile_path() -> tempfile::TempPath {
     )
     .unwrap();
 
-    return file.into_temp_path();
+    file.into_temp_path()
 }
 
 pub fn parse_scored_output(output: &str) -> Option<HashMap<String, f64>> {
@@ -60,17 +87,16 @@ pub fn parse_scored_output(output: &str) -> Option<HashMap<String, f64>> {
         out_map.insert(item.to_string(), score);
     }
 
-    return Some(out_map);
+    Some(out_map)
 }
 
 pub fn item_score_approx_equal(item: String, expected: f64) -> impl Predicate<[u8]> {
     predicates::function::function(move |x: &[u8]| {
         let map = parse_scored_output(str::from_utf8(x).expect("failed to parse utf8"));
-        let out_score = map
+        let out_score = *map
             .expect("failed to parse scored output")
             .get(&item.clone())
-            .expect("item doesn't exist in output")
-            .clone();
+            .expect("item doesn't exist in output");
         out_score >= expected * 0.95 && out_score <= expected * 1.05
     })
 }
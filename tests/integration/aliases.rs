@@ -0,0 +1,101 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn fre(store_file: &Path) -> Command {
+    let mut command = common::fre_command();
+    command.arg("--store").arg(store_file);
+    command
+}
+
+#[test]
+fn best_resolves_alias() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["alias", "set", "root", "/"])
+        .assert()
+        .success();
+
+    // The alias wins over /home, which matches better and has a higher score
+    fre(&store_file)
+        .args(["--best", "root"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/\n").from_utf8());
+
+    // Without an alias the highest scoring fuzzy match is printed
+    fre(&store_file)
+        .args(["best", "hm"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n").from_utf8());
+
+    fre(&store_file)
+        .args(["alias", "unset", "/"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["best", "root"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no item matches 'root'").from_utf8());
+}
+
+#[test]
+fn alias_unique() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["alias", "set", "h", "/home"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["alias", "set", "h", "/"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("alias 'h' is already used by /home").from_utf8());
+
+    fre(&store_file)
+        .args(["--sorted", "--format", "{alias}|{item}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("h|/home\n|/home/nonexistant_dir\n|/\n").from_utf8());
+}
+
+#[test]
+fn show_labels() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["label", "set", "home", "/home"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["--sorted", "--show", "label"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("home\n/home/nonexistant_dir\n/\n").from_utf8());
+
+    fre(&store_file)
+        .args(["list", "--show", "both", "--limit", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("home\t/home\n").from_utf8());
+
+    // Labels are fuzzy matched by --best
+    fre(&store_file)
+        .args(["label", "set", "top", "/"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["best", "top"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/\n").from_utf8());
+}
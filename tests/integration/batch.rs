@@ -0,0 +1,82 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn batch_operations() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--batch")
+        .with_stdin()
+        .buffer("add /home\nadd /new dir\nadjust /home 2\ndelete /\n")
+        .assert()
+        .success();
+
+    let expected =
+        predicate::str::similar("5\t/home\n1\t/home/nonexistant_dir\n1\t/new dir\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--sort_method")
+        .arg("frequent")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn batch_invalid_line() {
+    let store_file = common::get_tempfile_path();
+
+    let error = predicate::str::contains("line 2: unknown operation 'ad'").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--batch")
+        .with_stdin()
+        .buffer("add /new\nad /home\n")
+        .assert()
+        .code(1)
+        .stderr(error);
+
+    let unchanged = predicate::str::contains("/new").from_utf8().not();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .assert()
+        .stdout(unchanged);
+}
+
+#[test]
+fn batch_out_of_range() {
+    let store_file = common::get_tempfile_path();
+
+    let error =
+        predicate::str::contains("line 3: the frecency of /home would be out of range").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--batch")
+        .with_stdin()
+        .buffer("add /new\nadjust /home 1e308\nadjust /home 1e308\n")
+        .assert()
+        .code(1)
+        .stderr(error);
+
+    let unchanged = predicate::str::contains("/new").from_utf8().not();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .assert()
+        .stdout(unchanged);
+}
@@ -0,0 +1,59 @@
+use super::common;
+use assert_cmd::prelude::*;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn bash_completes_ranked_items() {
+    let data_home = tempfile::tempdir().unwrap();
+    for item in ["/a b", "/zzz", "/zzz", "/abc"] {
+        common::fre_command()
+            .env("XDG_DATA_HOME", data_home.path())
+            .arg("--add")
+            .arg(item)
+            .assert()
+            .success();
+    }
+
+    let bin_dir = Path::new(env!("CARGO_BIN_EXE_fre")).parent().unwrap();
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let script = r#"source <(fre completions bash)
+        COMP_WORDS=(fre --delete /a); COMP_CWORD=2; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}"
+        COMP_WORDS=(fre --sorted ""); COMP_CWORD=2; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}" | grep -c zzz
+        COMP_WORDS=(fre delete /z); COMP_CWORD=2; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}"
+        COMP_WORDS=(fre kind unset /z); COMP_CWORD=3; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}"
+        COMP_WORDS=(fre tag add work /z); COMP_CWORD=4; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}"
+        COMP_WORDS=(fre tag add /z); COMP_CWORD=3; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}" | grep -c zzz
+        COMP_WORDS=(fre adjust -2 /z); COMP_CWORD=3; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}"
+        COMP_WORDS=(fre adjust ""); COMP_CWORD=2; _fre_items
+        printf '%s\n' "${COMPREPLY[@]}" | grep -c zzz"#;
+    let output = common::isolate(&mut Command::new("bash"))
+        .env("XDG_DATA_HOME", data_home.path())
+        .env("PATH", path)
+        .arg("-c")
+        .arg(script)
+        .output();
+
+    match output {
+        Ok(output) => {
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout),
+                "/abc\n/a\\ b\n0\n/zzz\n/zzz\n/zzz\n0\n/zzz\n0\n"
+            );
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => panic!("failed to run bash: {}", e),
+    }
+}
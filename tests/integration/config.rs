@@ -0,0 +1,232 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+const CONFIG: &str = r#"
+sort_method = "frequent"
+limit = 1
+
+[profiles.clean]
+ignore = ["/tmp/*"]
+"#;
+
+/// A command running fre with a config file containing `config` and the fixture store
+fn fre(config_home: &Path, store_file: &Path) -> Command {
+    std::fs::create_dir_all(config_home.join("fre")).unwrap();
+    std::fs::write(config_home.join("fre").join("config.toml"), CONFIG).unwrap();
+
+    let mut command = common::fre_command();
+    command
+        .env("XDG_CONFIG_HOME", config_home)
+        .arg("--store")
+        .arg(store_file);
+    command
+}
+
+#[test]
+fn config_file_defaults() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    fre(config_home.path(), &store_file)
+        .arg("--sorted")
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/\n").from_utf8());
+
+    // The command line overrides the config file
+    fre(config_home.path(), &store_file)
+        .arg("--sorted")
+        .arg("--sort_method")
+        .arg("frecent")
+        .arg("--limit")
+        .arg("2")
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n/home/nonexistant_dir\n").from_utf8());
+}
+
+#[test]
+fn profile_ignore() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    fre(config_home.path(), &store_file)
+        .arg("--profile")
+        .arg("clean")
+        .arg("--add")
+        .arg("/tmp/scratch")
+        .arg("/new")
+        .assert()
+        .success();
+
+    fre(config_home.path(), &store_file)
+        .arg("list")
+        .arg("--limit")
+        .arg("10")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/new\n").from_utf8())
+        .stdout(predicate::str::contains("/tmp/scratch").from_utf8().not());
+}
+
+#[test]
+fn show_sources() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    let expected = format!(
+        "store\t{}\tcommand line\nnamespace\tdefault\tdefault\nhalf_life\t259200\tstore file\nsort_method\tfrequent\tconfig file\n",
+        store_file.display()
+    );
+
+    fre(config_home.path(), &store_file)
+        .arg("--profile")
+        .arg("clean")
+        .arg("config")
+        .arg("show")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("profile\tclean\n").from_utf8())
+        .stdout(predicate::str::contains(expected).from_utf8())
+        .stdout(predicate::str::contains("ignore\t/tmp/*\tprofile clean\n").from_utf8());
+}
+
+#[test]
+fn missing_profile() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    fre(config_home.path(), &store_file)
+        .arg("--profile")
+        .arg("work")
+        .arg("--sorted")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no profile 'work'").from_utf8());
+}
+
+#[test]
+fn environment_overrides() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    // FRE_SORT_METHOD overrides the config file, and --sort_method overrides both
+    fre(config_home.path(), &store_file)
+        .env("FRE_SORT_METHOD", "frecent")
+        .arg("--sorted")
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n").from_utf8());
+
+    fre(config_home.path(), &store_file)
+        .env("FRE_SORT_METHOD", "frecent")
+        .arg("--sorted")
+        .arg("--sort_method")
+        .arg("frequent")
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/\n").from_utf8());
+}
+
+#[test]
+fn environment_store() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .env("XDG_CONFIG_HOME", config_home.path())
+        .env("FRE_STORE", &store_file)
+        .arg("--sorted")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home/nonexistant_dir\n").from_utf8());
+}
+
+#[test]
+fn disable() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+
+    fre(config_home.path(), &store_file)
+        .env("FRE_DISABLE", "1")
+        .arg("--add")
+        .arg("/new")
+        .assert()
+        .success();
+
+    fre(config_home.path(), &store_file)
+        .env("FRE_DISABLE", "1")
+        .arg("--stat")
+        .arg("--limit")
+        .arg("5")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/new").from_utf8().not());
+
+    fre(config_home.path(), &store_file)
+        .env("FRE_HALFLIFE", "soon")
+        .arg("--sorted")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("invalid value 'soon' for FRE_HALFLIFE").from_utf8());
+}
+
+#[test]
+fn config_half_life_only_for_new_namespaces() {
+    let config_home = tempfile::tempdir().unwrap();
+    let store_file = common::get_tempfile_path();
+    std::fs::create_dir_all(config_home.path().join("fre")).unwrap();
+    std::fs::write(
+        config_home.path().join("fre").join("config.toml"),
+        "half_life = 86400\n",
+    )
+    .unwrap();
+    let fre = || {
+        let mut command = common::fre_command();
+        command
+            .env("XDG_CONFIG_HOME", config_home.path())
+            .arg("--store")
+            .arg(&store_file);
+        command
+    };
+    let half_life = |namespace: &str| {
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&store_file).unwrap()).unwrap();
+        match namespace {
+            "default" => json["half_life"].clone(),
+            _ => json["namespaces"][namespace]["half_life"].clone(),
+        }
+    };
+
+    // An existing namespace keeps its half life
+    fre().arg("--sorted").assert().success();
+    assert_eq!(half_life("default"), 259200.0);
+    fre()
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("half_life\t259200\tstore file\n").from_utf8());
+
+    // A new namespace gets the configured half life
+    fre()
+        .args(["--ns", "files", "--add", "/home/user/notes.md"])
+        .assert()
+        .success();
+    assert_eq!(half_life("files"), 86400.0);
+
+    // The command line and the environment change an existing namespace
+    fre()
+        .args(["--halflife", "1000", "--sorted"])
+        .assert()
+        .success();
+    assert_eq!(half_life("default"), 1000.0);
+    fre()
+        .env("FRE_HALFLIFE", "2000")
+        .arg("--sorted")
+        .assert()
+        .success();
+    assert_eq!(half_life("default"), 2000.0);
+}
@@ -1,7 +1,6 @@
+use super::common;
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
-use std::process::Command;
-use tempfile;
 
 #[test]
 fn invalid_store() {
@@ -9,8 +8,7 @@ fn invalid_store() {
     let error = predicates::str::contains("failed to read store file").from_utf8();
     let file = tempfile::NamedTempFile::new().unwrap();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
         .arg(file.path().as_os_str())
         .arg("--sorted")
@@ -25,8 +23,7 @@ fn non_writable() {
     let empty = predicates::str::is_empty().from_utf8();
     let error = predicates::str::is_empty().from_utf8().not();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
         .arg("/testdir")
         .arg("--sorted")
@@ -35,3 +32,89 @@ fn non_writable() {
         .stdout(empty)
         .stderr(error);
 }
+
+/// Run fre with `args` against a fixture store and assert that it fails with a usage error
+/// mentioning `message`, leaving the store unchanged
+fn assert_usage_error(args: &[&str], message: &str) {
+    let store_file = super::common::get_tempfile_path();
+    let before = std::fs::read(&store_file).unwrap();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .args(args)
+        .assert()
+        .code(2)
+        .stdout(predicates::str::is_empty().from_utf8())
+        .stderr(predicates::str::contains(message).from_utf8())
+        .stderr(predicates::str::contains("panicked").from_utf8().not());
+
+    assert_eq!(std::fs::read(&store_file).unwrap(), before);
+}
+
+#[test]
+fn add_without_item() {
+    assert_usage_error(&["--add"], "<--stdin|ITEM>");
+}
+
+#[test]
+fn delete_without_item() {
+    assert_usage_error(&["--delete"], "<--stdin|ITEM>");
+}
+
+#[test]
+fn increase_without_item() {
+    assert_usage_error(&["--increase", "3"], "<--stdin|ITEM>");
+}
+
+#[test]
+fn decrease_without_item() {
+    assert_usage_error(&["--decrease", "3"], "<--stdin|ITEM>");
+}
+
+#[test]
+fn increase_without_weight() {
+    assert_usage_error(
+        &["--increase"],
+        "a value is required for '--increase <WEIGHT>'",
+    );
+}
+
+#[test]
+fn add_at_without_item() {
+    assert_usage_error(&["--add", "--at", "1703721600"], "<--stdin|ITEM>");
+}
+
+#[test]
+fn invalid_half_life() {
+    for half_life in ["0", "-5", "inf", "NaN"] {
+        let message = format!("expected a positive number of seconds, got '{}'", half_life);
+        assert_usage_error(&["config", "halflife", half_life], &message);
+        assert_usage_error(&["--sorted", "--halflife", half_life], &message);
+    }
+}
+
+#[test]
+fn stdin_without_update() {
+    assert_usage_error(
+        &["--stdin"],
+        "<--add|--increase <WEIGHT>|--delete|--decrease <DECREASE>>",
+    );
+}
+
+#[test]
+fn subcommand_without_item() {
+    assert_usage_error(&["add"], "<ITEM>");
+    assert_usage_error(&["delete"], "<ITEM>");
+    assert_usage_error(&["adjust", "2"], "<ITEM>");
+    assert_usage_error(&["adjust"], "<WEIGHT>");
+}
+
+#[test]
+fn help_is_not_an_error() {
+    common::fre_command()
+        .arg("--help")
+        .assert()
+        .success()
+        .stderr(predicates::str::is_empty().from_utf8());
+}
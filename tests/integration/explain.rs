@@ -0,0 +1,36 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn explain_ranks() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(&store_file)
+        .args(["--explain", "/home"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("stored_frecency\t3.000\n")
+                .and(predicate::str::contains("num_accesses\t2\n"))
+                .and(predicate::str::contains("rank_frecent\t1 of 3\n"))
+                .and(predicate::str::contains("rank_frequent\t2 of 3\n"))
+                .and(predicate::str::contains("below_1\tin "))
+                .from_utf8(),
+        );
+}
+
+#[test]
+fn explain_missing_item() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(&store_file)
+        .args(["explain", "/missing"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("not in the store: /missing").from_utf8());
+}
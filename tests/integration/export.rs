@@ -0,0 +1,124 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn export_z_round_trip() {
+    let store_file = common::get_tempfile_path();
+    let dir = tempfile::tempdir().unwrap();
+    let database = dir.path().join("z");
+    let new_store = dir.path().join("fre.json");
+
+    let output = common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--export")
+        .arg("z")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    std::fs::write(&database, output.stdout).unwrap();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(&new_store)
+        .arg("--import")
+        .arg("z")
+        .arg(&database)
+        .assert()
+        .success();
+
+    let expected = predicate::str::similar("/home\n/home/nonexistant_dir\n/\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(&new_store)
+        .arg("--sorted")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn export_autojump() {
+    let store_file = common::get_tempfile_path();
+
+    // Weights are 10 * sqrt(frecency), with frecencies of just under 3, 2 and 1
+    let expected = predicate::str::starts_with("17.32")
+        .and(predicate::str::contains("\t/home\n14.14"))
+        .and(predicate::str::contains("\t/home/nonexistant_dir\n9.99"))
+        .and(predicate::str::ends_with("\t/\n"))
+        .from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--export")
+        .arg("autojump")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn export_conflicts_with_update() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--export")
+        .arg("csv")
+        .arg("--add")
+        .arg("/home")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn export_csv_round_trip() {
+    let store_file = common::get_tempfile_path();
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("fre.csv");
+    let new_store = dir.path().join("fre.json");
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("/with \"quotes\", and commas")
+        .assert()
+        .success();
+
+    let output = common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--export")
+        .arg("csv")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    std::fs::write(&csv, output.stdout).unwrap();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(&new_store)
+        .arg("--import")
+        .arg("csv")
+        .arg(&csv)
+        .assert()
+        .success();
+
+    let stat = |store: &std::path::Path| {
+        common::fre_command()
+            .arg("--store")
+            .arg(store)
+            .arg("--stat")
+            .output()
+            .unwrap()
+            .stdout
+    };
+    let original = String::from_utf8(stat(&store_file)).unwrap();
+
+    assert!(original.contains("\t/with \"quotes\", and commas\n"));
+    assert_eq!(String::from_utf8(stat(&new_store)).unwrap(), original);
+}
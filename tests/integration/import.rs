@@ -0,0 +1,127 @@
+use super::common;
+use assert_cmd::prelude::*;
+use fre::current_time_secs;
+use predicates::prelude::*;
+use std::io::Write;
+
+fn z_database() -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    let t = current_time_secs() as u64;
+    file.write_all(format!("/home|4|{}\n/src|10|{}\n", t, t).as_bytes())
+        .unwrap();
+    file
+}
+
+#[test]
+fn import_z() {
+    let store_file = common::get_tempfile_path();
+    let database = z_database();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--import")
+        .arg("z")
+        .arg(database.path())
+        .assert()
+        .success();
+
+    let expected =
+        predicate::str::similar("10\t/src\n6\t/home\n3\t/\n1\t/home/nonexistant_dir\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--sort_method")
+        .arg("frequent")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn import_preview() {
+    let store_file = common::get_tempfile_path();
+    let database = z_database();
+
+    let expected = predicate::str::similar("10.000\t/src\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--import")
+        .arg("fasd")
+        .arg(database.path())
+        .arg("--preview")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(expected);
+
+    let unchanged = predicate::str::contains("/src").from_utf8().not();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .assert()
+        .stdout(unchanged);
+}
+
+#[test]
+fn import_invalid_kind() {
+    let store_file = common::get_tempfile_path();
+
+    let error = predicate::str::contains("invalid import format 'jump'").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--import")
+        .arg("jump")
+        .arg("/nonexistent")
+        .assert()
+        .code(1)
+        .stderr(error);
+}
+
+#[test]
+fn import_zsh_history() {
+    let store_file = common::get_tempfile_path();
+    let mut history = tempfile::NamedTempFile::new().unwrap();
+    let t = current_time_secs() as u64;
+    history
+        .write_all(
+            format!(
+                ": {}:0;cd /srv\n: {}:0;cd app && vim main.rs\n: {}:0;cd ~\n",
+                t - 2,
+                t - 1,
+                t
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+    common::fre_command()
+        .env("HOME", "/home/test")
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--import")
+        .arg("zsh-history")
+        .arg(history.path())
+        .assert()
+        .success();
+
+    let expected = predicate::str::contains("\n/home/test\n")
+        .and(predicate::str::contains("\n/srv\n"))
+        .and(predicate::str::contains("\n/srv/app\n"))
+        .and(predicate::str::contains("main.rs").not())
+        .from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .assert()
+        .stdout(expected);
+}
@@ -0,0 +1,133 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Print the integration script for `shell`
+fn init_script(shell: &str) -> Vec<u8> {
+    let output = common::fre_command()
+        .arg("init")
+        .arg(shell)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    output.stdout
+}
+
+#[test]
+fn init_scripts_parse() {
+    // Each check runs the program with the arguments, with {} replaced by the script path
+    let checks: [(&str, &str, &[&str]); 5] = [
+        ("bash", "bash", &["-n", "{}"]),
+        ("zsh", "zsh", &["-n", "{}"]),
+        ("fish", "fish", &["--no-execute", "{}"]),
+        (
+            "nushell",
+            "nu",
+            &["--no-config-file", "--commands", "nu-check --debug '{}'"],
+        ),
+        ("elvish", "elvish", &["-compileonly", "{}"]),
+    ];
+
+    for (shell, program, args) in checks {
+        let script = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(script.path(), init_script(shell)).unwrap();
+
+        let path = script.path().to_str().unwrap();
+        let args = args.iter().map(|a| a.replace("{}", path));
+        match Command::new(program).args(args).output() {
+            Ok(output) => assert!(
+                output.status.success(),
+                "{} script is invalid:\n{}",
+                shell,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                eprintln!(
+                    "skipping {} script check, {} is not installed",
+                    shell, program
+                )
+            }
+            Err(e) => panic!("failed to run {}: {}", program, e),
+        }
+    }
+}
+
+#[test]
+fn bash_hook_adds_on_change() {
+    let home = tempfile::tempdir().unwrap();
+    let visited = tempfile::tempdir().unwrap();
+    let store_file = home.path().join("fre").join("fre.json");
+    let bin_dir = Path::new(env!("CARGO_BIN_EXE_fre")).parent().unwrap();
+    let path = format!(
+        "{}:{}",
+        bin_dir.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    // The hook only adds when the directory changes, and not while FRE_DISABLE is set
+    let script = r#"eval "$(fre init bash)"
+        cd "$1"; __fre_hook; __fre_hook
+        FRE_DISABLE=1; cd /; __fre_hook"#;
+    let status = common::isolate(&mut Command::new("bash"))
+        .env("HOME", home.path())
+        .env("XDG_DATA_HOME", home.path())
+        .env("PATH", path)
+        .arg("-c")
+        .arg(script)
+        .arg("bash")
+        .arg(visited.path())
+        .status();
+    match status {
+        Ok(status) => assert!(status.success()),
+        Err(e) if e.kind() == ErrorKind::NotFound => return,
+        Err(e) => panic!("failed to run bash: {}", e),
+    }
+
+    // Directories are added in the background, so poll the store until the visit shows up
+    let expected = format!("1\t{}\n", visited.path().display());
+    let stat = || {
+        let output = common::fre_command()
+            .arg("--store")
+            .arg(&store_file)
+            .arg("--stat")
+            .arg("--sort_method")
+            .arg("frequent")
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let start = Instant::now();
+    let mut stdout = stat();
+    while stdout != expected && start.elapsed() < Duration::from_secs(10) {
+        std::thread::sleep(Duration::from_millis(50));
+        stdout = stat();
+    }
+
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn item_named_like_subcommand() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("init")
+        .arg("list")
+        .assert()
+        .success();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .assert()
+        .stdout(predicate::str::contains("\ninit\n").from_utf8())
+        .stdout(predicate::str::contains("\nlist\n").from_utf8());
+}
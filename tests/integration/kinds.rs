@@ -0,0 +1,79 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn fre(store_file: &Path) -> Command {
+    let mut command = common::fre_command();
+    command.arg("--store").arg(store_file);
+    command
+}
+
+#[test]
+fn filter_by_kind() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["--add", "https://github.com"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["add", "--kind", "command", "--", "ls -la"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["--sorted", "--kind", "url"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("https://github.com\n").from_utf8());
+
+    fre(&store_file)
+        .args(["list", "--kind", "command", "--format", "{kind} {item}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("command ls -la\n").from_utf8());
+
+    // Items stored without a kind have it guessed
+    fre(&store_file)
+        .args(["list", "--kind", "dir", "--sort_method", "frequent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/\n/home\n").from_utf8());
+}
+
+#[test]
+fn missing_only_for_paths() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["kind", "set", "other", "/home/nonexistant_dir"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["gc", "--missing"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["--sorted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n/home/nonexistant_dir\n/\n").from_utf8());
+
+    fre(&store_file)
+        .args(["kind", "unset", "/home/nonexistant_dir"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["gc", "--missing"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["--sorted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n/\n").from_utf8());
+}
@@ -1,5 +1,20 @@
+mod aliases;
+mod batch;
+mod completions;
+mod config;
 mod errors;
+mod explain;
+mod export;
+mod import;
+mod init;
+mod kinds;
+mod namespaces;
+mod null;
+mod output;
 mod sort;
+mod subcommands;
+mod subtree;
+mod tags;
 mod weight;
 
 use super::common;
@@ -0,0 +1,110 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn fre(store_file: &Path) -> Command {
+    let mut command = common::fre_command();
+    command.arg("--store").arg(store_file);
+    command
+}
+
+#[test]
+fn namespaces_are_separate() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["--ns", "files", "--add", "/home/user/notes.md"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["--ns", "files", "--sorted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home/user/notes.md\n").from_utf8());
+
+    fre(&store_file)
+        .arg("--sorted")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("notes.md").from_utf8().not());
+
+    // The default namespace stays at the top level, where older versions read it
+    let json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&store_file).unwrap()).unwrap();
+    assert_eq!(json["items"].as_array().unwrap().len(), 3);
+    assert_eq!(
+        json["namespaces"]["files"]["items"][0]["item"],
+        "/home/user/notes.md"
+    );
+}
+
+#[test]
+fn reading_missing_namespace() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["--ns", "empty", "--sorted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().from_utf8());
+
+    fre(&store_file)
+        .args(["ns", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("default\n").from_utf8());
+}
+
+#[test]
+fn copy_rename_delete() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["ns", "copy", "default", "backup"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["ns", "copy", "backup", "old"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["ns", "rename", "backup", "dirs"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["ns", "delete", "old"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["ns", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("default\ndirs\n").from_utf8());
+
+    fre(&store_file)
+        .args(["--ns", "dirs", "--sorted"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/home/nonexistant_dir\n").from_utf8());
+}
+
+#[test]
+fn invalid_namespace_changes() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["ns", "delete", "default"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("cannot delete the default namespace").from_utf8());
+
+    fre(&store_file)
+        .args(["ns", "rename", "missing", "other"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("no namespace 'missing'").from_utf8());
+}
@@ -0,0 +1,96 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn sorted_null() {
+    let store_file = common::get_tempfile_path();
+
+    let expected = predicate::str::similar("/home\0/home/nonexistant_dir\0/\0").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("-0")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn add_stdin() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--stdin")
+        .with_stdin()
+        .buffer("/home\n/new dir\n/home\n")
+        .assert()
+        .success();
+
+    let home_added_twice = common::item_score_approx_equal("/home".to_string(), 4.0);
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--sort_method")
+        .arg("frequent")
+        .assert()
+        .stdout(home_added_twice);
+}
+
+#[test]
+fn delete_stdin_null() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--stdin")
+        .arg("--null")
+        .with_stdin()
+        .buffer("/with\nnewline\0")
+        .assert()
+        .success();
+
+    let expected = predicate::str::similar("/home\0/home/nonexistant_dir\0/\0").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--delete")
+        .arg("--stdin")
+        .arg("--null")
+        .with_stdin()
+        .buffer("/with\nnewline\0")
+        .assert()
+        .success();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--null")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn stdin_conflicts_with_item() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--stdin")
+        .arg("/home")
+        .assert()
+        .failure();
+}
@@ -0,0 +1,120 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn sorted_jsonl() {
+    let store_file = common::get_tempfile_path();
+
+    let output = common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--output")
+        .arg("jsonl")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let records: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    let items: Vec<&str> = records
+        .iter()
+        .map(|r| r["item"].as_str().unwrap())
+        .collect();
+    assert_eq!(items, vec!["/home", "/home/nonexistant_dir", "/"]);
+    assert_eq!(records[0]["rank"], 1);
+    assert_eq!(records[0]["num_accesses"], 2);
+    assert!(records[0]["last_accessed_rfc3339"]
+        .as_str()
+        .unwrap()
+        .ends_with('Z'));
+}
+
+#[test]
+fn stat_json_limit() {
+    let store_file = common::get_tempfile_path();
+
+    let output = common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--sort_method")
+        .arg("frequent")
+        .arg("--limit")
+        .arg("1")
+        .arg("--output")
+        .arg("json")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let records: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(records.as_array().unwrap().len(), 1);
+    assert_eq!(records[0]["item"], "/");
+    assert_eq!(records[0]["num_accesses"], 3);
+}
+
+#[test]
+fn sorted_format() {
+    let store_file = common::get_tempfile_path();
+
+    let expected =
+        predicates::str::similar("1 2 /home\n2 1 /home/nonexistant_dir\n3 3 /\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--format")
+        .arg("{rank} {accesses} {item}")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn invalid_format() {
+    let store_file = common::get_tempfile_path();
+
+    let error = predicates::str::contains("unknown field 'bogus'").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--format")
+        .arg("{bogus}")
+        .assert()
+        .failure()
+        .stderr(error);
+}
+
+#[test]
+fn stat_all_scores() {
+    let store_file = common::get_tempfile_path();
+
+    let output = common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--all-scores")
+        .arg("--totals")
+        .arg("--sort_method")
+        .arg("frequent")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[0], vec!["FRECENT", "FREQUENT", "HOURS", "ITEM"]);
+    assert_eq!(rows[1][1..2], ["3"]);
+    assert_eq!(rows[1][3], "/");
+    assert_eq!(rows[4][0..2], ["6.000", "6"]);
+    assert_eq!(rows[4][3], "TOTAL (3 items)");
+}
@@ -1,7 +1,6 @@
 use super::common;
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
-use std::process::Command;
 use std::str;
 
 #[test]
@@ -11,10 +10,9 @@ fn sorted_stats() {
     let expected_sorted =
         predicate::str::similar("3\t/\n2\t/home\n1\t/home/nonexistant_dir\n").from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .arg("--sort_method")
         .arg("frequent")
@@ -28,10 +26,9 @@ fn sorted_frecent() {
 
     let expected_sorted = predicate::str::similar("/home\n/home/nonexistant_dir\n/\n").from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .assert()
         .stdout(expected_sorted);
@@ -43,10 +40,9 @@ fn sorted_recent() {
 
     let expected_sorted = predicate::str::similar("/home/nonexistant_dir\n/\n/home\n").from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .arg("--sort_method")
         .arg("recent")
@@ -60,10 +56,9 @@ fn sorted_frequent() {
 
     let expected_sorted = predicate::str::similar("/\n/home\n/home/nonexistant_dir\n").from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .arg("--sort_method")
         .arg("frequent")
@@ -77,10 +72,9 @@ fn sorted_invalid() {
 
     let expected_error = predicate::str::contains("invalid value 'badsort'").from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .arg("--sort_method")
         .arg("badsort")
@@ -92,10 +86,9 @@ fn sorted_invalid() {
 fn truncate() {
     let store_file = common::get_tempfile_path();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--truncate")
         .arg("2")
         .assert()
@@ -103,10 +96,9 @@ fn truncate() {
 
     let two_lines = predicate::function(|x: &[u8]| str::from_utf8(x).unwrap().lines().count() == 2);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .assert()
         .stdout(two_lines);
@@ -118,10 +110,9 @@ fn limit() {
 
     let two_lines = common::n_results(2);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .arg("--limit")
         .arg("2")
@@ -136,10 +127,9 @@ fn limit_too_many() {
 
     let three_lines = common::n_results(3);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .arg("--limit")
         .arg("4")
@@ -152,10 +142,9 @@ fn limit_too_many() {
 fn change_half_life_maintain_frecency() {
     let store_file = common::get_tempfile_path();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--halflife")
         .arg("1000")
         .assert()
@@ -163,10 +152,9 @@ fn change_half_life_maintain_frecency() {
 
     let score_same = common::item_score_approx_equal("/".to_string(), 1.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .assert()
         .stdout(score_same);
@@ -176,10 +164,9 @@ fn change_half_life_maintain_frecency() {
 fn change_half_life_new_decay() {
     let store_file = common::get_tempfile_path();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--halflife")
         .arg("100.0")
         .assert()
@@ -187,10 +174,9 @@ fn change_half_life_new_decay() {
 
     let score_half = common::item_score_approx_equal("/home".to_string(), 3.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .assert()
         .stdout(score_half);
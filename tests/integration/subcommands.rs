@@ -0,0 +1,93 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn update_and_list() {
+    let store_file = common::get_tempfile_path();
+
+    for args in [
+        vec!["add", "/new"],
+        vec!["adjust", "-2", "/home"],
+        vec!["delete", "/"],
+    ] {
+        common::fre_command()
+            .arg("--store")
+            .arg(store_file.as_os_str())
+            .args(&args)
+            .assert()
+            .success();
+    }
+
+    let expected =
+        predicate::str::similar("1\t/home/nonexistant_dir\n1\t/new\n0\t/home\n").from_utf8();
+
+    common::fre_command()
+        .arg("stat")
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sort_method")
+        .arg("frequent")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn gc_threshold_and_keep() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("gc")
+        .arg("--threshold")
+        .arg("1.5")
+        .arg("--keep")
+        .arg("1")
+        .assert()
+        .success();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("list")
+        .assert()
+        .stdout(predicate::str::similar("/home\n").from_utf8());
+}
+
+#[test]
+fn config_halflife() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("config")
+        .arg("halflife")
+        .arg("100")
+        .assert()
+        .success();
+
+    let expected = predicate::str::contains("half_life\t100\tstore file\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("config")
+        .arg("show")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn subcommand_requires_item() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("delete")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("<ITEM>").from_utf8());
+}
@@ -0,0 +1,135 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+
+#[test]
+fn sorted_under() {
+    let store_file = common::get_tempfile_path();
+
+    let expected = predicate::str::similar("/home/nonexistant_dir\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--under")
+        .arg("/home")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn sorted_under_relative() {
+    let store_file = common::get_tempfile_path();
+
+    let expected = predicate::str::similar("home\nhome/nonexistant_dir\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--under")
+        .arg("/")
+        .arg("--relative")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn sorted_under_relative_dir() {
+    let store_file = common::get_tempfile_path();
+    let dir = tempfile::tempdir().unwrap();
+    let cwd = dir.path().join("a");
+    std::fs::create_dir(&cwd).unwrap();
+    let item = cwd.join("b");
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg(&item)
+        .assert()
+        .success();
+
+    let expected = predicate::str::similar(format!("{}\n", item.display())).from_utf8();
+
+    common::fre_command()
+        .current_dir(&cwd)
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--under")
+        .arg("..")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn stat_under_rollup() {
+    let store_file = common::get_tempfile_path();
+
+    let rolled_up = common::item_score_approx_equal("/home".to_string(), 5.0);
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--under")
+        .arg("/")
+        .arg("--rollup")
+        .assert()
+        .success()
+        .stdout(rolled_up);
+}
+
+#[test]
+fn relative_requires_under() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--relative")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn tree() {
+    let store_file = common::get_tempfile_path();
+
+    let expected =
+        predicate::str::similar("6.000\t6\t/\n5.000\t3\t  home\n2.000\t1\t    nonexistant_dir\n")
+            .from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--tree")
+        .assert()
+        .success()
+        .stdout(expected);
+}
+
+#[test]
+fn tree_under_depth() {
+    let store_file = common::get_tempfile_path();
+
+    let expected = predicate::str::similar("2.000\t1\tnonexistant_dir\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--tree")
+        .arg("--under")
+        .arg("/home")
+        .arg("--depth")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(expected);
+}
@@ -0,0 +1,105 @@
+use super::common;
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+fn fre(store_file: &Path) -> Command {
+    let mut command = common::fre_command();
+    command.arg("--store").arg(store_file);
+    command
+}
+
+#[test]
+fn filter_by_tag() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["tag", "add", "repo", "/home", "/"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["tag", "add", "work", "/home"])
+        .assert()
+        .success();
+    // Tags are kept when an item is visited again
+    fre(&store_file).args(["--add", "/home"]).assert().success();
+
+    fre(&store_file)
+        .args(["--sorted", "--tag", "repo", "--sort_method", "frequent"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/\n/home\n").from_utf8());
+
+    fre(&store_file)
+        .args(["list", "--tag", "repo", "--tag", "work"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n").from_utf8());
+
+    fre(&store_file)
+        .args(["tag", "remove", "repo", "/"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args(["--sorted", "--tag", "repo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home\n").from_utf8());
+}
+
+#[test]
+fn filter_by_metadata() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["meta", "set", "host=laptop", "/home", "/"])
+        .assert()
+        .success();
+    fre(&store_file)
+        .args(["meta", "unset", "host", "/"])
+        .assert()
+        .success();
+
+    fre(&store_file)
+        .args([
+            "--sorted",
+            "--meta",
+            "host=laptop",
+            "--format",
+            "{item} {meta.host}",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::similar("/home laptop\n").from_utf8());
+}
+
+#[test]
+fn tag_missing_item() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["tag", "add", "repo", "/home", "/missing"])
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("not in the store: /missing").from_utf8());
+
+    // Nothing is tagged unless every item is in the store
+    fre(&store_file)
+        .args(["--sorted", "--tag", "repo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty().from_utf8());
+}
+
+#[test]
+fn invalid_metadata() {
+    let store_file = common::get_tempfile_path();
+
+    fre(&store_file)
+        .args(["meta", "set", "=laptop", "/home"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("expected KEY=VALUE").from_utf8());
+}
@@ -1,27 +1,24 @@
 use super::common;
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
-use std::process::Command;
 
 #[test]
 fn add_existing_exists() {
     let store_file = common::get_tempfile_path();
     let dir = "/home".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--add")
         .arg(&dir)
         .assert();
 
     let exists = predicates::str::contains(dir).from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .assert()
         .stdout(exists);
@@ -32,20 +29,18 @@ fn add_existing_increases() {
     let store_file = common::get_tempfile_path();
     let dir = "/home".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--add")
         .arg(&dir)
         .assert();
 
     let increased = common::item_score_approx_equal(dir, 3.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .arg("--sort_method")
         .arg("frequent")
@@ -58,10 +53,9 @@ fn add_create() {
     let store_file = common::get_tempfile_path();
     let new_dir = "/home/super_new_dir".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--add")
         .arg(&new_dir)
         .assert()
@@ -69,24 +63,53 @@ fn add_create() {
 
     let exists = predicates::str::contains(new_dir).from_utf8();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--sorted")
         .assert()
         .stdout(exists);
 }
 
+#[test]
+fn add_and_delete_many() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .args(["--add", "/home", "/new_a", "/new_b"])
+        .assert()
+        .success();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .args(["--delete", "/", "/new_b"])
+        .assert()
+        .success();
+
+    let expected =
+        predicate::str::similar("3\t/home\n1\t/home/nonexistant_dir\n1\t/new_a\n").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--sort_method")
+        .arg("frequent")
+        .assert()
+        .stdout(expected);
+}
+
 #[test]
 fn increase_accesses() {
     let store_file = common::get_tempfile_path();
     let absolute_dir = "/home".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--increase")
         .arg("2.0")
         .arg(&absolute_dir)
@@ -95,10 +118,9 @@ fn increase_accesses() {
 
     let accesses_increased_two = common::item_score_approx_equal(absolute_dir.clone(), 4.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .arg("--sort_method")
         .arg("frequent")
@@ -111,11 +133,10 @@ fn decrease_accesses() {
     let store_file = common::get_tempfile_path();
     let absolute_dir = "/home".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .current_dir(std::env::temp_dir().as_os_str())
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--decrease")
         .arg("1.0")
         .arg(&absolute_dir)
@@ -124,11 +145,10 @@ fn decrease_accesses() {
 
     let accesses_decreased_one = common::item_score_approx_equal(absolute_dir.clone(), 1.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .current_dir(std::env::temp_dir().as_os_str())
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .arg("--sort_method")
         .arg("frequent")
@@ -141,11 +161,10 @@ fn increase_score() {
     let store_file = common::get_tempfile_path();
     let absolute_dir = "/home".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .current_dir(std::env::temp_dir().as_os_str())
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--increase")
         .arg("2.0")
         .arg(&absolute_dir)
@@ -154,11 +173,10 @@ fn increase_score() {
 
     let frecency_increased_two = common::item_score_approx_equal(absolute_dir.clone(), 5.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .current_dir(std::env::temp_dir().as_os_str())
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .arg("--sort_method")
         .arg("frecent")
@@ -171,11 +189,10 @@ fn decrease_score() {
     let store_file = common::get_tempfile_path();
     let absolute_dir = "/home".to_string();
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .current_dir(std::env::temp_dir().as_os_str())
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--decrease")
         .arg("1.0")
         .arg(&absolute_dir)
@@ -184,14 +201,128 @@ fn decrease_score() {
 
     let frecency_decreased_one = common::item_score_approx_equal(absolute_dir.clone(), 2.0);
 
-    Command::main_binary()
-        .unwrap()
+    common::fre_command()
         .current_dir(std::env::temp_dir().as_os_str())
         .arg("--store")
-        .arg(&store_file.as_os_str())
+        .arg(store_file.as_os_str())
         .arg("--stat")
         .arg("--sort_method")
         .arg("frecent")
         .assert()
         .stdout(frecency_decreased_one);
 }
+
+#[test]
+fn add_at_past_time() {
+    let store_file = common::get_tempfile_path();
+    let new_dir = "/home/old_dir".to_string();
+    let one_half_life_ago = fre::current_time_secs() - 259200.0;
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--at")
+        .arg(one_half_life_ago.to_string())
+        .arg(&new_dir)
+        .assert()
+        .success();
+
+    let decayed = common::item_score_approx_equal(new_dir.clone(), 0.5);
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .assert()
+        .stdout(decayed);
+
+    let one_half_life_old = common::item_score_approx_equal(new_dir, 72.0);
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--stat")
+        .arg("--sort_method")
+        .arg("recent")
+        .assert()
+        .stdout(one_half_life_old);
+}
+
+#[test]
+fn add_at_rfc3339() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--at")
+        .arg("2000-01-01T00:00:00Z")
+        .arg("/home/ancient")
+        .assert()
+        .success();
+
+    let expected = predicates::str::contains("2000-01-01T00:00:00Z").from_utf8();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--sorted")
+        .arg("--format")
+        .arg("{last_access:rfc3339} {item}")
+        .assert()
+        .stdout(expected);
+}
+
+#[test]
+fn add_at_invalid_time() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--at")
+        .arg("last tuesday")
+        .arg("/home")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn add_at_future_time() {
+    let store_file = common::get_tempfile_path();
+
+    common::fre_command()
+        .arg("--store")
+        .arg(store_file.as_os_str())
+        .arg("--add")
+        .arg("--at")
+        .arg("2100-01-01T00:00:00Z")
+        .arg("/home")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("is in the future").from_utf8());
+}
+
+#[test]
+fn add_at_out_of_range_time() {
+    let store_file = common::get_tempfile_path();
+
+    for time in [
+        "9223372036854775807-01-01T00:00:00Z",
+        "2023-12-28T00:00:00+9999999999999999:00",
+    ] {
+        common::fre_command()
+            .arg("--store")
+            .arg(store_file.as_os_str())
+            .arg("--add")
+            .arg("--at")
+            .arg(time)
+            .arg("/home")
+            .assert()
+            .code(2)
+            .stderr(predicate::str::contains("invalid timestamp").from_utf8());
+    }
+}